@@ -0,0 +1,130 @@
+use std::error::Error;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Runs a shell command and returns its standard output
+///
+/// The command runs in its own process group. If it doesn't complete before `timeout`, the whole
+/// group is killed, including the processes started by the command.
+///
+/// ## Example
+///
+/// ```
+/// use mqtt_system_monitor::command;
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let output = command::run("echo 42", Duration::from_secs(1))
+///     .await
+///     .expect("Command failed");
+///
+/// assert_eq!(output, "42\n");
+/// # }
+/// ```
+pub async fn run(command: &str, timeout: Duration) -> Result<String, Box<dyn Error>> {
+    let mut child = Command::new("sh");
+    child.arg("-c").arg(command);
+    output(child, command, timeout).await
+}
+
+/// Runs a program with arguments, without a shell, and returns its standard output
+///
+/// The program is killed like in [run] if it doesn't complete before `timeout`.
+pub async fn execute(
+    program: &str,
+    args: &[&str],
+    timeout: Duration,
+) -> Result<String, Box<dyn Error>> {
+    let mut child = Command::new(program);
+    child.args(args);
+    output(child, program, timeout).await
+}
+
+/// Waits for the output of a command, `name` is the command shown in the errors
+async fn output(
+    mut command: Command,
+    name: &str,
+    timeout: Duration,
+) -> Result<String, Box<dyn Error>> {
+    let child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .kill_on_drop(true)
+        .spawn()?;
+    let pid = child.id();
+
+    // The output is complete when every process holding the pipe exited, not only the shell
+    let output = match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(output) => output?,
+        Err(_) => {
+            if let Some(pid) = pid {
+                kill_group(pid);
+            }
+            return Err(format!("`{name}` timed out after {timeout:?}").into());
+        }
+    };
+
+    if !output.status.success() {
+        return Err(format!("`{name}` failed with {}", output.status).into());
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Kills all the processes of a process group
+fn kill_group(pgid: u32) {
+    // SAFETY: killpg only sends a signal, an invalid group is reported as an error
+    unsafe {
+        libc::killpg(pgid as libc::pid_t, libc::SIGKILL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn test_run() {
+        assert_eq!(
+            run("printf 'a\\nb'", Duration::from_secs(1)).await.unwrap(),
+            "a\nb"
+        );
+
+        assert!(run("exit 1", Duration::from_secs(1)).await.is_err());
+
+        let start = Instant::now();
+        assert!(run("sleep 5", Duration::from_millis(100)).await.is_err());
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // A background process still holding the output is killed with the shell
+        let start = Instant::now();
+        assert!(
+            run("sleep 5 & echo started", Duration::from_millis(100))
+                .await
+                .is_err()
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_execute() {
+        // The arguments are not interpreted by a shell
+        assert_eq!(
+            execute("echo", &["$HOME", "a b"], Duration::from_secs(1))
+                .await
+                .unwrap(),
+            "$HOME a b\n"
+        );
+
+        assert!(
+            execute("/nonexistent/program", &[], Duration::from_secs(1))
+                .await
+                .is_err()
+        );
+    }
+}
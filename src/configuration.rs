@@ -1,5 +1,10 @@
-use serde::Deserialize;
+use crate::home_assistant::{key, mount_key};
+use crate::hwmon::HWMON_ROOT;
+use crate::power_supply::POWER_SUPPLY_ROOT;
+use rumqttc::QoS;
+use serde::{Deserialize, Deserializer};
 use serde_inline_default::serde_inline_default;
+use std::collections::HashMap;
 use std::error::Error;
 
 /// Contains the configuration for communicating with the MQTT broker
@@ -38,18 +43,364 @@ pub struct Mqtt {
     /// Name of the device entity. It should be unique in Home Assistant. Default: machine hostname
     #[serde(default = "hostname")]
     pub entity: String,
+
+    /// Quality of service of the discovery messages, 0, 1 or 2. Default: 1
+    #[serde_inline_default(QoS::AtLeastOnce)]
+    #[serde(rename = "discovery-qos", deserialize_with = "qos")]
+    pub discovery_qos: QoS,
+
+    /// If set, the discovery messages are retained by the broker. Default: true
+    #[serde_inline_default(true)]
+    #[serde(rename = "discovery-retain")]
+    pub discovery_retain: bool,
+
+    /// Quality of service of the state messages, 0, 1 or 2. Default: 1
+    #[serde_inline_default(QoS::AtLeastOnce)]
+    #[serde(rename = "state-qos", deserialize_with = "qos")]
+    pub state_qos: QoS,
+
+    /// If set, the state messages are retained by the broker. Default: false
+    #[serde(default, rename = "state-retain")]
+    pub state_retain: bool,
+
+    /// Transport of the connection to the broker. Default: tcp
+    #[serde(default)]
+    pub transport: Transport,
+
+    /// Path of the URL of the broker, for the WebSocket transports. Default: /mqtt
+    #[serde_inline_default(String::from("/mqtt"))]
+    pub path: String,
+
+    /// TLS settings of the connection to the broker. Default: none
+    ///
+    /// If set, the `tcp` transport is encrypted with TLS. The `wss` transport uses the default
+    /// settings if this is not set.
+    pub tls: Option<Tls>,
+}
+
+/// Transports of the connection to the broker
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    /// MQTT over TCP, encrypted with TLS if `mqtt.tls` is set
+    #[default]
+    Tcp,
+
+    /// MQTT over WebSocket
+    Ws,
+
+    /// MQTT over WebSocket, encrypted with TLS
+    Wss,
+}
+
+/// Contains the TLS configuration of the connection to the broker
+#[derive(Deserialize, Debug, Default)]
+pub struct Tls {
+    /// PEM file containing the certificate authorities trusted to sign the broker certificate.
+    /// Default: the certificate authorities of the system
+    #[serde(default, rename = "ca-file")]
+    pub ca_file: Option<String>,
+
+    /// PEM file containing the client certificate chain. Default: none
+    #[serde(default, rename = "client-cert")]
+    pub client_cert: Option<String>,
+
+    /// PEM file containing the private key of the client certificate. Default: none
+    #[serde(default, rename = "client-key")]
+    pub client_key: Option<String>,
+
+    /// Protocols offered with ALPN, for example `mqtt`. Default: empty
+    #[serde(default)]
+    pub alpn: Vec<String>,
+
+    /// If set, the certificate of the broker is not verified. Default: false
+    ///
+    /// This makes the connection vulnerable to man-in-the-middle attacks and should only be
+    /// used for testing
+    #[serde(default, rename = "insecure-skip-verify")]
+    pub insecure_skip_verify: bool,
+}
+
+/// Additional memory sensors
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MemorySensor {
+    /// Swap usage in %
+    Swap,
+
+    /// Available memory in bytes
+    Available,
+
+    /// Memory used by the page cache in bytes
+    Cached,
+
+    /// Memory used by the buffers in bytes
+    Buffers,
+}
+
+/// Package managers queried for the pending updates
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageManager {
+    /// Debian, Ubuntu and derivatives, with `apt-get`
+    Apt,
+
+    /// Fedora and RHEL derivatives, with `dnf`
+    Dnf,
+
+    /// Arch Linux and derivatives, with `checkupdates` from `pacman-contrib`
+    Pacman,
+}
+
+/// Default delay between each check of the pending updates in seconds
+pub const DEFAULT_UPDATES_INTERVAL: u64 = 3600;
+
+/// Contains the configuration of the pending updates sensor
+///
+/// With `apt`, the package lists are not refreshed before the check, so the count is only as
+/// recent as the last `apt update` run outside `mqtt-system-monitor`, for example by the
+/// `apt-daily` timer.
+#[serde_inline_default]
+#[derive(Deserialize, Debug, Clone)]
+pub struct Updates {
+    /// Package manager to query
+    #[serde(rename = "package-manager")]
+    pub package_manager: PackageManager,
+
+    /// Delay between each check of the pending updates and of the reboot required sensor in
+    /// seconds. Default: 3600 seconds
+    #[serde_inline_default(DEFAULT_UPDATES_INTERVAL)]
+    pub interval: u64,
+
+    /// Maximum duration of the check in seconds, after which it is killed. Default: 300 seconds
+    #[serde_inline_default(300)]
+    pub timeout: u64,
+}
+
+/// Default hysteresis of the temperature thresholds in °C
+pub const DEFAULT_HYSTERESIS: f32 = 2.0;
+
+/// Contains the thresholds of a temperature sensor
+///
+/// Each threshold has its own problem sensor, cleared with the same hysteresis.
+#[serde_inline_default]
+#[derive(Deserialize, Debug, Clone)]
+pub struct TemperatureThreshold {
+    /// Temperature in °C above which a warning is reported. Default: none
+    #[serde(default)]
+    pub warning: Option<f32>,
+
+    /// Temperature in °C above which a critical problem is reported.
+    /// Default: the critical temperature reported by the sensor, if any
+    #[serde(default)]
+    pub critical: Option<f32>,
+
+    /// Decrease of the temperature in °C below the threshold needed to clear the problem. Default: 2
+    #[serde_inline_default(DEFAULT_HYSTERESIS)]
+    pub hysteresis: f32,
+}
+
+impl Default for TemperatureThreshold {
+    fn default() -> Self {
+        TemperatureThreshold {
+            warning: None,
+            critical: None,
+            hysteresis: DEFAULT_HYSTERESIS,
+        }
+    }
+}
+
+/// Contains the configuration of a watched process
+///
+/// The process is searched with the first field that is set among `pidfile`, `exe` and
+/// `process-name`. All the matching processes are accounted together.
+#[derive(Deserialize, Debug)]
+pub struct Process {
+    /// Name of the sensor, shown in Home Assistant
+    pub name: String,
+
+    /// Name of the process to search. Default: `name`
+    #[serde(default, rename = "process-name")]
+    pub process_name: Option<String>,
+
+    /// Path of the executable of the process to search
+    #[serde(default)]
+    pub exe: Option<String>,
+
+    /// Path of a file containing the PID of the process to search
+    #[serde(default)]
+    pub pidfile: Option<String>,
+}
+
+/// Contains the configuration of a custom command sensor
+#[serde_inline_default]
+#[derive(Deserialize, Debug, Clone)]
+pub struct CommandSensor {
+    /// Name of the sensor, shown in Home Assistant
+    pub name: String,
+
+    /// Shell command whose standard output is the value of the sensor
+    pub command: String,
+
+    /// Delay between each run of the command in seconds. Default: `mqtt.update-period`
+    #[serde(default)]
+    pub interval: Option<u64>,
+
+    /// Maximum duration of the command in seconds, after which it is killed. Default: 5 seconds
+    #[serde_inline_default(5)]
+    pub timeout: u64,
+
+    /// Unit of the value. The value is considered as a measurement if set. Default: none
+    #[serde(default)]
+    pub unit: Option<String>,
+
+    /// Device class of the value in Home Assistant. Default: none
+    ///
+    /// See <https://www.home-assistant.io/integrations/sensor#device-class> for possible values here
+    #[serde(default, rename = "device-class")]
+    pub device_class: Option<String>,
+
+    /// If set, the output is parsed as JSON and the value is searched with this path. Default: none
+    ///
+    /// The path contains object keys or array indices separated by dots, for example `ups.0.charge`
+    #[serde(default, rename = "json-path")]
+    pub json_path: Option<String>,
+}
+
+/// Contains the configuration of a file sensor
+#[serde_inline_default]
+#[derive(Deserialize, Debug)]
+pub struct FileSensor {
+    /// Name of the sensor, shown in Home Assistant
+    pub name: String,
+
+    /// Path of the file to read. It can contain `*` and `?` wildcards, the first match is read
+    pub path: String,
+
+    /// Factor applied to numeric values. Default: 1
+    #[serde_inline_default(1.0)]
+    pub scale: f64,
+
+    /// Unit of the value. The value is considered as a measurement if set. Default: none
+    #[serde(default)]
+    pub unit: Option<String>,
+
+    /// Device class of the value in Home Assistant. Default: none
+    ///
+    /// See <https://www.home-assistant.io/integrations/sensor#device-class> for possible values here
+    #[serde(default, rename = "device-class")]
+    pub device_class: Option<String>,
 }
 
 /// Contains the configuration for the sensors
+#[serde_inline_default]
 #[derive(Deserialize)]
 pub struct Sensors {
     /// If set, contains a list of temperature ids to search in `sysinfo`'s component reports.
     #[serde(default)]
     pub temperature: Vec<String>,
 
+    /// Thresholds of the temperature sensors, indexed by temperature id.
+    ///
+    /// A warning and a critical problem sensors are reported for the temperatures having these
+    /// thresholds. The critical one can also be the critical temperature reported by `sysinfo`.
+    #[serde(default, rename = "temperature-thresholds")]
+    pub temperature_thresholds: HashMap<String, TemperatureThreshold>,
+
+    /// If set, contains a list of fan ids to search in the hardware monitors, for example `hwmon1_fan1`.
+    #[serde(default)]
+    pub fan: Vec<String>,
+
+    /// If set, contains a list of voltage ids to search in the hardware monitors, for example `hwmon1_in0`.
+    #[serde(default)]
+    pub voltage: Vec<String>,
+
+    /// Location of the hardware monitors. Default: /sys/class/hwmon
+    #[serde_inline_default(String::from(HWMON_ROOT))]
+    #[serde(rename = "hwmon-root")]
+    pub hwmon_root: String,
+
     /// If set, contains a list of network interface to monitor.
     #[serde(default)]
     pub network: Vec<String>,
+
+    /// If set, reports the total amount of data received and transmitted by the network
+    /// interfaces. Default: false
+    #[serde(default, rename = "network-totals")]
+    pub network_totals: bool,
+
+    /// If set, reports the link state, the IP addresses and the MAC address of the network
+    /// interfaces. Default: false
+    #[serde(default, rename = "network-details")]
+    pub network_details: bool,
+
+    /// If set, reports the rates of packets, errors and dropped packets of the network
+    /// interfaces. Default: false
+    #[serde(default, rename = "network-packets")]
+    pub network_packets: bool,
+
+    /// If set, contains a list of mount points to monitor.
+    #[serde(default, deserialize_with = "mount_points")]
+    pub disk: Vec<String>,
+
+    /// If set, contains a list of block devices to monitor.
+    #[serde(default, rename = "disk-io")]
+    pub disk_io: Vec<String>,
+
+    /// If set, reports the usage and the frequency of each logical core. Default: false
+    #[serde(default, rename = "per-cpu")]
+    pub per_cpu: bool,
+
+    /// If set, contains a list of additional memory sensors
+    #[serde(default)]
+    pub memory: Vec<MemorySensor>,
+
+    /// If set, contains a list of processes to watch
+    #[serde(default, deserialize_with = "processes")]
+    pub process: Vec<Process>,
+
+    /// If set, contains a list of systemd units to monitor.
+    #[serde(default, rename = "systemd-units", deserialize_with = "names")]
+    pub systemd_units: Vec<String>,
+
+    /// If set, contains a list of software RAID arrays to monitor.
+    #[serde(default, deserialize_with = "names")]
+    pub raid: Vec<String>,
+
+    /// If set, contains a list of ZFS pools to monitor.
+    #[serde(default, rename = "zfs-pools", deserialize_with = "names")]
+    pub zfs_pools: Vec<String>,
+
+    /// If set, reports if a reboot is required to apply the updates, checked every
+    /// `updates.interval` seconds. Default: false
+    #[serde(default, rename = "reboot-required")]
+    pub reboot_required: bool,
+
+    /// If set, reports the number of pending updates. Default: none
+    #[serde(default)]
+    pub updates: Option<Updates>,
+
+    /// If set, contains a list of custom command sensors
+    #[serde(default, deserialize_with = "commands")]
+    pub command: Vec<CommandSensor>,
+
+    /// If set, contains a list of file sensors
+    #[serde(default, deserialize_with = "files")]
+    pub file: Vec<FileSensor>,
+
+    /// If set, reports the level of the battery and the state of the AC adapter. Default: false
+    #[serde(default, rename = "power-supply")]
+    pub power_supply: bool,
+
+    /// Location of the power supplies. Default: /sys/class/power_supply
+    #[serde_inline_default(String::from(POWER_SUPPLY_ROOT))]
+    #[serde(rename = "power-supply-root")]
+    pub power_supply_root: String,
+
+    /// If set, reports the Pressure Stall Information of the CPU, the memory and the I/O.
+    /// Default: false
+    #[serde(default)]
+    pub pressure: bool,
 }
 
 /// Contains all the configuration for `mqtt-system-monitor`
@@ -77,6 +428,131 @@ fn hostname() -> String {
     sysinfo::System::host_name().expect("Cannot read hostname")
 }
 
+/// Reads a quality of service from its level
+fn qos<'de, D>(deserializer: D) -> Result<QoS, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    rumqttc::qos(u8::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+}
+
+/// Reads a list of mount points, rejecting the ones that would have the same key
+fn mount_points<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mounts = Vec::<String>::deserialize(deserializer)?;
+    unique_keys(mounts.iter().map(String::as_str), mount_key)?;
+
+    Ok(mounts)
+}
+
+/// Reads a list of names, rejecting the ones that would have an empty or the same key
+fn names<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let names = Vec::<String>::deserialize(deserializer)?;
+    unique_keys(names.iter().map(String::as_str), key)?;
+
+    Ok(names)
+}
+
+/// Reads a list of processes, rejecting the names that would have an empty or the same key
+fn processes<'de, D>(deserializer: D) -> Result<Vec<Process>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let processes = Vec::<Process>::deserialize(deserializer)?;
+    unique_keys(processes.iter().map(|process| process.name.as_str()), key)?;
+
+    Ok(processes)
+}
+
+/// Reads a list of command sensors, rejecting the names that would have an empty or the same key
+fn commands<'de, D>(deserializer: D) -> Result<Vec<CommandSensor>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let commands = Vec::<CommandSensor>::deserialize(deserializer)?;
+    unique_keys(commands.iter().map(|command| command.name.as_str()), key)?;
+
+    Ok(commands)
+}
+
+/// Reads a list of file sensors, rejecting the names that would have an empty or the same key
+fn files<'de, D>(deserializer: D) -> Result<Vec<FileSensor>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let files = Vec::<FileSensor>::deserialize(deserializer)?;
+    unique_keys(files.iter().map(|file| file.name.as_str()), key)?;
+
+    Ok(files)
+}
+
+/// Checks that the names have distinct and non-empty keys, as the keys identify the sensors
+fn unique_keys<'a, E>(
+    names: impl Iterator<Item = &'a str>,
+    key: fn(&str) -> String,
+) -> Result<(), E>
+where
+    E: serde::de::Error,
+{
+    let mut keys = HashMap::new();
+    for name in names {
+        let key = key(name);
+        if key.is_empty() {
+            return Err(E::custom(format!("the name {name:?} has an empty key")));
+        }
+        if let Some(other) = keys.insert(key, name) {
+            return Err(E::custom(format!(
+                "the names {other:?} and {name:?} have the same key"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+impl Mqtt {
+    /// Returns the URL of the broker
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use mqtt_system_monitor::configuration::Mqtt;
+    ///
+    /// let mqtt: Mqtt = toml::from_str(
+    ///     r#"
+    ///     entity = "laptop"
+    ///     host = "homeassistant.lan"
+    ///     port = 443
+    ///     transport = "wss"
+    ///     "#,
+    /// ).expect("Cannot parse configuration");
+    ///
+    /// assert_eq!(mqtt.url(), "wss://homeassistant.lan:443/mqtt");
+    /// ```
+    pub fn url(&self) -> String {
+        // IPv6 addresses are enclosed in brackets in URLs
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+
+        let path = format!("/{}", self.path.trim_start_matches('/'));
+
+        match self.transport {
+            Transport::Tcp if self.tls.is_some() => format!("mqtts://{host}:{}", self.port),
+            Transport::Tcp => format!("mqtt://{host}:{}", self.port),
+            Transport::Ws => format!("ws://{host}:{}{path}", self.port),
+            Transport::Wss => format!("wss://{host}:{}{path}", self.port),
+        }
+    }
+}
+
 impl Configuration {
     /// Load the configuration from a file
     ///
@@ -105,13 +581,352 @@ mod tests {
 
         assert_eq!(conf.mqtt.host, String::from("localhost"));
         assert_eq!(conf.mqtt.registration_prefix, String::from("homeassistant"));
+        assert_eq!(conf.mqtt.discovery_qos, QoS::AtLeastOnce);
+        assert!(conf.mqtt.discovery_retain);
+        assert_eq!(conf.mqtt.state_qos, QoS::AtLeastOnce);
+        assert!(!conf.mqtt.state_retain);
+        assert!(conf.mqtt.tls.is_none());
+        assert_eq!(conf.mqtt.transport, Transport::Tcp);
+        assert_eq!(conf.mqtt.path, "/mqtt");
 
         // By default, the entity name will be the hostname of the machine
         assert_eq!(conf.mqtt.entity, hostname());
 
         // Sensors are off by default
         assert!(conf.sensors.temperature.is_empty());
+        assert!(conf.sensors.temperature_thresholds.is_empty());
         assert!(conf.sensors.network.is_empty());
+        assert!(!conf.sensors.network_totals);
+        assert!(!conf.sensors.network_details);
+        assert!(!conf.sensors.network_packets);
+        assert!(conf.sensors.fan.is_empty());
+        assert!(conf.sensors.voltage.is_empty());
+        assert_eq!(conf.sensors.hwmon_root, HWMON_ROOT);
+        assert!(conf.sensors.disk.is_empty());
+        assert!(conf.sensors.disk_io.is_empty());
+        assert!(!conf.sensors.per_cpu);
+        assert!(conf.sensors.memory.is_empty());
+        assert!(conf.sensors.process.is_empty());
+        assert!(conf.sensors.systemd_units.is_empty());
+        assert!(conf.sensors.raid.is_empty());
+        assert!(conf.sensors.zfs_pools.is_empty());
+        assert!(!conf.sensors.reboot_required);
+        assert!(conf.sensors.updates.is_none());
+        assert!(conf.sensors.command.is_empty());
+        assert!(conf.sensors.file.is_empty());
+        assert!(!conf.sensors.power_supply);
+        assert_eq!(conf.sensors.power_supply_root, POWER_SUPPLY_ROOT);
+        assert!(!conf.sensors.pressure);
+
+        Ok(())
+    }
+
+    /// Test that the additional memory sensors are parsed
+    #[test]
+    fn test_memory_sensors() -> Result<(), Box<dyn Error>> {
+        let sensors: Sensors = toml::from_str(r#"memory = [ "swap", "cached" ]"#)?;
+
+        assert_eq!(
+            sensors.memory,
+            vec![MemorySensor::Swap, MemorySensor::Cached]
+        );
+
+        Ok(())
+    }
+
+    /// Test that the watched processes are parsed
+    #[test]
+    fn test_processes() -> Result<(), Box<dyn Error>> {
+        let sensors: Sensors = toml::from_str(
+            r#"
+            [[process]]
+            name = "plex"
+            process-name = "Plex Media Serv"
+
+            [[process]]
+            name = "postgres"
+            pidfile = "/run/postgresql/postgres.pid"
+            "#,
+        )?;
+
+        assert_eq!(sensors.process.len(), 2);
+        assert_eq!(
+            sensors.process[0].process_name.as_deref(),
+            Some("Plex Media Serv")
+        );
+        assert_eq!(sensors.process[0].pidfile, None);
+        assert_eq!(
+            sensors.process[1].pidfile.as_deref(),
+            Some("/run/postgresql/postgres.pid")
+        );
+
+        Ok(())
+    }
+
+    /// Test that the custom command sensors are parsed
+    #[test]
+    fn test_commands() -> Result<(), Box<dyn Error>> {
+        let sensors: Sensors = toml::from_str(
+            r#"
+            [[command]]
+            name = "Pending updates"
+            command = "apt list --upgradable | wc -l"
+            interval = 3600
+
+            [[command]]
+            name = "UPS charge"
+            command = "upsc -j ups"
+            unit = "%"
+            device-class = "battery"
+            json-path = "battery.charge"
+            "#,
+        )?;
+
+        assert_eq!(sensors.command.len(), 2);
+        assert_eq!(sensors.command[0].interval, Some(3600));
+        assert_eq!(sensors.command[0].timeout, 5);
+        assert_eq!(sensors.command[0].unit, None);
+        assert_eq!(sensors.command[1].device_class.as_deref(), Some("battery"));
+        assert_eq!(
+            sensors.command[1].json_path.as_deref(),
+            Some("battery.charge")
+        );
+
+        Ok(())
+    }
+
+    /// Test that the quality of service and the retain flags are parsed
+    #[test]
+    fn test_publish_settings() -> Result<(), Box<dyn Error>> {
+        let mqtt: Mqtt = toml::from_str(
+            r#"
+            entity = "test"
+            discovery-qos = 2
+            discovery-retain = false
+            state-qos = 0
+            "#,
+        )?;
+
+        assert_eq!(mqtt.discovery_qos, QoS::ExactlyOnce);
+        assert!(!mqtt.discovery_retain);
+        assert_eq!(mqtt.state_qos, QoS::AtMostOnce);
+        assert!(!mqtt.state_retain);
+
+        assert!(toml::from_str::<Mqtt>("entity = \"test\"\nstate-qos = 3").is_err());
+
+        Ok(())
+    }
+
+    /// Test that mount points with the same key are rejected
+    #[test]
+    fn test_mount_points() -> Result<(), Box<dyn Error>> {
+        let sensors: Sensors = toml::from_str(r#"disk = [ "/", "/root", "/var/lib" ]"#)?;
+        assert_eq!(sensors.disk, vec!["/", "/root", "/var/lib"]);
+
+        assert!(toml::from_str::<Sensors>(r#"disk = [ "/var/lib", "/var_lib" ]"#).is_err());
+
+        Ok(())
+    }
+
+    /// Test that the names of the sensors must have distinct and non-empty keys
+    #[test]
+    fn test_names() -> Result<(), Box<dyn Error>> {
+        let sensors: Sensors = toml::from_str(r#"systemd-units = [ "sshd.service", "sshd" ]"#)?;
+        assert_eq!(sensors.systemd_units, vec!["sshd.service", "sshd"]);
+
+        assert!(toml::from_str::<Sensors>(r#"raid = [ "md-0", "md_0" ]"#).is_err());
+        assert!(toml::from_str::<Sensors>(r#"zfs-pools = [ "tank", "tank." ]"#).is_err());
+        assert!(toml::from_str::<Sensors>(r#"raid = [ "/" ]"#).is_err());
+
+        let processes = toml::from_str::<Sensors>(
+            r#"
+            [[process]]
+            name = "Test process"
+
+            [[process]]
+            name = "Test-process"
+            "#,
+        );
+        assert!(processes.is_err());
+
+        let commands = toml::from_str::<Sensors>(
+            r#"
+            [[command]]
+            name = "***"
+            command = "true"
+            "#,
+        );
+        assert!(commands.is_err());
+
+        let files = toml::from_str::<Sensors>(
+            r#"
+            [[file]]
+            name = "UPS status"
+            path = "/run/status"
+
+            [[file]]
+            name = "UPS-status"
+            path = "/run/other"
+            "#,
+        );
+        assert!(files.is_err());
+
+        Ok(())
+    }
+
+    /// Test that the TLS settings are parsed
+    #[test]
+    fn test_tls() -> Result<(), Box<dyn Error>> {
+        let mqtt: Mqtt = toml::from_str(
+            r#"
+            entity = "test"
+            port = 8883
+
+            [tls]
+            ca-file = "/etc/ssl/broker-ca.pem"
+            client-cert = "/etc/ssl/client.pem"
+            client-key = "/etc/ssl/client.key"
+            alpn = [ "mqtt" ]
+            "#,
+        )?;
+
+        let tls = mqtt.tls.expect("tls not parsed");
+        assert_eq!(tls.ca_file.as_deref(), Some("/etc/ssl/broker-ca.pem"));
+        assert_eq!(tls.client_cert.as_deref(), Some("/etc/ssl/client.pem"));
+        assert_eq!(tls.client_key.as_deref(), Some("/etc/ssl/client.key"));
+        assert_eq!(tls.alpn, vec!["mqtt"]);
+        assert!(!tls.insecure_skip_verify);
+
+        let mqtt: Mqtt =
+            toml::from_str("entity = \"test\"\ntls = { insecure-skip-verify = true }")?;
+        let tls = mqtt.tls.expect("tls not parsed");
+        assert_eq!(tls.ca_file, None);
+        assert!(tls.alpn.is_empty());
+        assert!(tls.insecure_skip_verify);
+
+        Ok(())
+    }
+
+    /// Test the URL of the broker for each transport
+    #[test]
+    fn test_url() -> Result<(), Box<dyn Error>> {
+        let mqtt: Mqtt = toml::from_str("entity = \"test\"")?;
+        assert_eq!(mqtt.url(), "mqtt://localhost:1883");
+
+        let mqtt: Mqtt = toml::from_str("entity = \"test\"\nport = 8883\ntls = {}")?;
+        assert_eq!(mqtt.url(), "mqtts://localhost:8883");
+
+        let mqtt: Mqtt = toml::from_str(
+            r#"
+            entity = "test"
+            host = "::1"
+            port = 8123
+            transport = "ws"
+            path = "api/mqtt"
+            "#,
+        )?;
+        assert_eq!(mqtt.transport, Transport::Ws);
+        assert_eq!(mqtt.url(), "ws://[::1]:8123/api/mqtt");
+
+        assert!(toml::from_str::<Mqtt>("entity = \"test\"\ntransport = \"quic\"").is_err());
+
+        Ok(())
+    }
+
+    /// Test that the pending updates sensor is parsed
+    #[test]
+    fn test_updates() -> Result<(), Box<dyn Error>> {
+        let sensors: Sensors = toml::from_str(
+            r#"
+            reboot-required = true
+
+            [updates]
+            package-manager = "dnf"
+            "#,
+        )?;
+
+        assert!(sensors.reboot_required);
+        let updates = sensors.updates.expect("updates not parsed");
+        assert_eq!(updates.package_manager, PackageManager::Dnf);
+        assert_eq!(updates.interval, 3600);
+        assert_eq!(updates.timeout, 300);
+
+        assert!(
+            toml::from_str::<Sensors>(
+                "[updates]
+package-manager = \"zypper\""
+            )
+            .is_err()
+        );
+
+        Ok(())
+    }
+
+    /// Test that the file sensors are parsed
+    #[test]
+    fn test_files() -> Result<(), Box<dyn Error>> {
+        let sensors: Sensors = toml::from_str(
+            r#"
+            [[file]]
+            name = "Battery"
+            path = "/sys/class/power_supply/BAT0/capacity"
+            unit = "%"
+            device-class = "battery"
+
+            [[file]]
+            name = "Brightness"
+            path = "/sys/class/backlight/*/brightness"
+            scale = 0.01
+            "#,
+        )?;
+
+        assert_eq!(sensors.file.len(), 2);
+        assert_eq!(sensors.file[0].scale, 1.0);
+        assert_eq!(sensors.file[0].device_class.as_deref(), Some("battery"));
+        assert_eq!(sensors.file[1].scale, 0.01);
+        assert_eq!(sensors.file[1].unit, None);
+
+        Ok(())
+    }
+
+    /// Test that the temperature thresholds are parsed
+    #[test]
+    fn test_temperature_thresholds() -> Result<(), Box<dyn Error>> {
+        let sensors: Sensors = toml::from_str(
+            r#"
+            temperature = [ "hwmon0_1", "hwmon0_2" ]
+
+            [temperature-thresholds.hwmon0_1]
+            warning = 70
+
+            [temperature-thresholds.hwmon0_2]
+            critical = 90
+            hysteresis = 5
+
+            [temperature-thresholds.hwmon0_3]
+            warning = 70
+            critical = 90
+            "#,
+        )?;
+
+        let threshold = &sensors.temperature_thresholds["hwmon0_1"];
+        assert_eq!(threshold.warning, Some(70.0));
+        assert_eq!(threshold.critical, None);
+        assert_eq!(threshold.hysteresis, 2.0);
+
+        let threshold = &sensors.temperature_thresholds["hwmon0_2"];
+        assert_eq!(threshold.warning, None);
+        assert_eq!(threshold.critical, Some(90.0));
+        assert_eq!(threshold.hysteresis, 5.0);
+
+        let threshold = &sensors.temperature_thresholds["hwmon0_3"];
+        assert_eq!(threshold.warning, Some(70.0));
+        assert_eq!(threshold.critical, Some(90.0));
+
+        assert_eq!(
+            TemperatureThreshold::default().hysteresis,
+            DEFAULT_HYSTERESIS
+        );
 
         Ok(())
     }
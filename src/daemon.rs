@@ -1,15 +1,56 @@
-use crate::configuration::Configuration;
-use crate::home_assistant::{RegistrationDescriptor, Sensor};
-use crate::status::{NetworkStatus, StatusMessage};
-use log::{debug, error, info, trace};
-use rumqttc::{AsyncClient, ClientError, MqttOptions, QoS};
+use crate::command;
+use crate::configuration::{
+    self, CommandSensor, Configuration, DEFAULT_UPDATES_INTERVAL, MemorySensor, Process,
+    TemperatureThreshold, Tls,
+};
+use crate::diskstats::{self, DiskStats};
+use crate::file;
+use crate::home_assistant::{
+    PAYLOAD_AVAILABLE, PAYLOAD_NOT_AVAILABLE, RegistrationDescriptor, Sensor, key, mount_key,
+};
+use crate::hwmon;
+use crate::mdstat;
+use crate::meminfo;
+use crate::net::{self, NET_ROOT};
+use crate::power_supply;
+use crate::pressure::{self, PRESSURE_ROOT, Pressure};
+use crate::status::{
+    CpuStatus, DiskIoStatus, DiskStatus, NetworkStatus, ProcessStatus, RaidStatus, StatusMessage,
+    SystemdUnitStatus, ZfsPoolStatus,
+};
+use crate::statvfs;
+use crate::systemd;
+use crate::tls;
+use crate::updates::{self, BOOT_ROOT, MODULES_ROOT, REBOOT_REQUIRED_PATH};
+use crate::value;
+use crate::wireless;
+use crate::zfs;
+use log::{debug, error, info, trace, warn};
+use rumqttc::{
+    AsyncClient, ClientError, Event, LastWill, MqttOptions, Packet, QoS, TlsConfiguration,
+    Transport,
+};
+use serde_json::Value;
 use std::collections::HashMap;
 use std::error::Error;
-use sysinfo::{Components, CpuRefreshKind, MemoryRefreshKind, Networks, RefreshKind, System};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use sysinfo::{
+    Components, CpuRefreshKind, Disks, MemoryRefreshKind, Networks, Pid, ProcessRefreshKind,
+    ProcessesToUpdate, RefreshKind, System, UpdateKind,
+};
 use tokio::signal::unix::SignalKind;
-use tokio::task;
+use tokio::sync::mpsc;
+use tokio::task::{self, JoinSet};
 use tokio::time::sleep;
 
+/// Delay before the first reconnection attempt to the broker, doubled after each failure
+const RECONNECT_DELAY_MIN: Duration = Duration::from_secs(1);
+
+/// Maximum delay between the reconnection attempts to the broker
+const RECONNECT_DELAY_MAX: Duration = Duration::from_secs(60);
+
 /// Daemon that periodically sends reports to MQTT
 pub struct Daemon {
     config: Configuration,
@@ -19,6 +60,32 @@ pub struct Daemon {
     system: System,
     network: Networks,
     components: Components,
+    disks: Disks,
+    disk_stats: HashMap<String, DiskStats>,
+    net_drops: HashMap<String, net::Drops>,
+    temperature_warnings: HashMap<String, bool>,
+    temperature_criticals: HashMap<String, bool>,
+    collected: Arc<Mutex<Collected>>,
+    collectors: JoinSet<()>,
+}
+
+/// Values of the slow sensors, refreshed by background tasks so that they don't delay the updates
+#[derive(Debug, Default)]
+struct Collected {
+    /// Values of the custom commands, `null` if a command failed
+    commands: HashMap<String, Value>,
+
+    /// States of the systemd units
+    systemd: HashMap<String, systemd::UnitState>,
+
+    /// States of the ZFS pools
+    zfs: HashMap<String, zfs::ZfsPool>,
+
+    /// Number of pending updates, `None` until the first check completes or if it failed
+    pending_updates: Option<u64>,
+
+    /// Tells if a reboot is required, `None` until the first check completes
+    reboot_required: Option<bool>,
 }
 
 impl Daemon {
@@ -28,11 +95,13 @@ impl Daemon {
     /// use mqtt_system_monitor::{Configuration, Daemon};
     ///
     /// let config = Configuration::load("conf/mqtt-system-monitor.conf").expect("Cannot load configuration");
-    /// let mut daemon = Daemon::new(config);
+    /// let mut daemon = Daemon::new(config).expect("Cannot create daemon");
     ///
     /// // later, run daemon.run() in an async function
     /// ```
-    pub fn new(config: Configuration) -> Daemon {
+    ///
+    /// Fails if the TLS settings are set but the certificates or the key cannot be loaded
+    pub fn new(config: Configuration) -> Result<Daemon, Box<dyn Error>> {
         info!("Daemon for {} starting", config.mqtt.entity);
 
         let system = System::new_with_specifics(
@@ -45,23 +114,81 @@ impl Daemon {
 
         let components = Components::new_with_refreshed_list();
 
-        let mut mqtt_config =
-            MqttOptions::new(&config.mqtt.entity, &config.mqtt.host, config.mqtt.port);
+        let disks = Disks::new_with_refreshed_list();
+
+        let disk_stats = if config.sensors.disk_io.is_empty() {
+            HashMap::new()
+        } else {
+            diskstats::read()
+        };
+
+        let net_drops = if config.sensors.network_packets {
+            config
+                .sensors
+                .network
+                .iter()
+                .filter_map(|interface| {
+                    net::drops(Path::new(NET_ROOT), interface)
+                        .map(|drops| (interface.clone(), drops))
+                })
+                .collect()
+        } else {
+            HashMap::new()
+        };
+
+        let registration_descriptor = RegistrationDescriptor::new(&config.mqtt.entity);
+
+        let mut mqtt_config = match config.mqtt.transport {
+            configuration::Transport::Tcp => {
+                MqttOptions::new(&config.mqtt.entity, &config.mqtt.host, config.mqtt.port)
+            }
+            // The WebSocket transports read the address of the broker from the URL
+            configuration::Transport::Ws | configuration::Transport::Wss => {
+                MqttOptions::new(&config.mqtt.entity, config.mqtt.url(), config.mqtt.port)
+            }
+        };
         mqtt_config.set_credentials(&config.mqtt.user, &config.mqtt.password);
+        // The broker reports the device as unavailable if the connection is lost
+        mqtt_config.set_last_will(LastWill::new(
+            registration_descriptor.availability_topic(),
+            PAYLOAD_NOT_AVAILABLE,
+            QoS::AtLeastOnce,
+            true,
+        ));
+        match (&config.mqtt.transport, &config.mqtt.tls) {
+            (configuration::Transport::Tcp, None) => {}
+            (configuration::Transport::Tcp, Some(tls)) => {
+                mqtt_config.set_transport(Transport::tls_with_config(tls_configuration(tls)?));
+            }
+            (configuration::Transport::Ws, tls) => {
+                if tls.is_some() {
+                    warn!("The TLS settings are ignored by the ws transport, use wss instead");
+                }
+                mqtt_config.set_transport(Transport::ws());
+            }
+            (configuration::Transport::Wss, tls) => {
+                let tls = tls_configuration(tls.as_ref().unwrap_or(&Tls::default()))?;
+                mqtt_config.set_transport(Transport::wss_with_config(tls));
+            }
+        }
 
-        info!(
-            "Connecting to MQTT broker {}:{}",
-            config.mqtt.host, config.mqtt.port
-        );
+        info!("Connecting to MQTT broker {}", config.mqtt.url());
 
-        Daemon {
+        Ok(Daemon {
             mqtt_config,
-            registration_descriptor: RegistrationDescriptor::new(&config.mqtt.entity),
+            registration_descriptor,
             system,
             network,
             components,
+            disks,
+            disk_stats,
+            net_drops,
+            temperature_warnings: HashMap::new(),
+            temperature_criticals: HashMap::new(),
+            collected: Default::default(),
+            collectors: JoinSet::new(),
             config,
-        }
+        })
     }
 
     /// Updates the data and returns a status message
@@ -69,14 +196,32 @@ impl Daemon {
         if self.registration_descriptor.has_sensor(Sensor::CpuUsage) {
             self.system.refresh_cpu_usage();
         }
+        if self.config.sensors.per_cpu {
+            self.system.refresh_cpu_frequency();
+        }
         if self.registration_descriptor.has_sensor(Sensor::MemoryUsage) {
             self.system.refresh_memory();
         }
 
+        if !self.config.sensors.process.is_empty() {
+            self.system.refresh_processes_specifics(
+                ProcessesToUpdate::All,
+                true,
+                ProcessRefreshKind::nothing()
+                    .with_cpu()
+                    .with_memory()
+                    .with_exe(UpdateKind::OnlyIfNotSet),
+            );
+        }
+
         if !self.config.sensors.network.is_empty() {
             self.network.refresh(true);
         }
 
+        if !self.config.sensors.disk.is_empty() {
+            self.disks.refresh(true);
+        }
+
         for temp_id in &self.config.sensors.temperature {
             for component in self.components.iter_mut() {
                 if let Some(id) = component.id()
@@ -87,27 +232,183 @@ impl Daemon {
             }
         }
 
+        let power_supply = if self.config.sensors.power_supply {
+            power_supply::read(Path::new(&self.config.sensors.power_supply_root))
+        } else {
+            Default::default()
+        };
+        let temperature = self.select_temperature();
+        let temperature_warning = self.select_temperature_warning(&temperature);
+        let temperature_critical = self.select_temperature_critical(&temperature);
+        let load_average = System::load_average();
+        let meminfo = if self
+            .registration_descriptor
+            .has_sensor(Sensor::MemoryCached)
+            || self
+                .registration_descriptor
+                .has_sensor(Sensor::MemoryBuffers)
+        {
+            meminfo::read()
+        } else {
+            HashMap::new()
+        };
+
         StatusMessage {
-            available: "ON",
             cpu_usage: Some(self.system.global_cpu_usage()),
+            cpus: self.select_cpus(),
             memory_usage: Some(
                 100.0 * (self.system.used_memory() as f32 / self.system.total_memory() as f32),
             ),
-            temperature: self.select_temperature(),
+            swap_usage: self
+                .registration_descriptor
+                .has_sensor(Sensor::SwapUsage)
+                .then(|| match self.system.total_swap() {
+                    0 => 0.0,
+                    total => 100.0 * (self.system.used_swap() as f32 / total as f32),
+                }),
+            memory_available: self
+                .registration_descriptor
+                .has_sensor(Sensor::MemoryAvailable)
+                .then(|| self.system.available_memory()),
+            memory_cached: self
+                .registration_descriptor
+                .has_sensor(Sensor::MemoryCached)
+                .then(|| meminfo.get("Cached").copied())
+                .flatten(),
+            memory_buffers: self
+                .registration_descriptor
+                .has_sensor(Sensor::MemoryBuffers)
+                .then(|| meminfo.get("Buffers").copied())
+                .flatten(),
+            battery_level: power_supply.battery_level,
+            battery_charging: power_supply.battery_charging.map(on_off),
+            ac_connected: power_supply.ac_connected.map(on_off),
+            load_average_1: Some(load_average.one),
+            load_average_5: Some(load_average.five),
+            load_average_15: Some(load_average.fifteen),
+            boot_time: Some(timestamp(System::boot_time())),
+            reboot_required: self.select_reboot_required(),
+            pending_updates: self.select_pending_updates(),
+            temperature,
+            temperature_warning,
+            temperature_critical,
+            fan: self.select_hwmon(&self.config.sensors.fan),
+            voltage: self.select_hwmon(&self.config.sensors.voltage),
             network: self.select_network(),
+            disk: self.select_disk(),
+            disk_io: self.select_disk_io(),
+            process: self.select_process(),
+            systemd: self.select_systemd(),
+            raid: self.select_raid(),
+            zfs: self.select_zfs(),
+            command: self.select_command(),
+            file: self.select_file(),
+            pressure: self.select_pressure(),
         }
     }
 
+    /// Selects the Pressure Stall Information of each resource, if enabled
+    fn select_pressure(&self) -> HashMap<String, Pressure> {
+        let mut map = HashMap::new();
+        if !self.config.sensors.pressure {
+            return map;
+        }
+
+        for resource in pressure::RESOURCES {
+            if let Some(pressure) = pressure::read(Path::new(PRESSURE_ROOT), resource) {
+                map.insert(resource.to_string(), pressure);
+            }
+        }
+
+        map
+    }
+
+    /// Selects the current values of each logical core, if enabled
+    fn select_cpus(&self) -> Vec<CpuStatus> {
+        if !self.config.sensors.per_cpu {
+            return Vec::new();
+        }
+
+        self.system
+            .cpus()
+            .iter()
+            .map(|cpu| CpuStatus {
+                usage: cpu.cpu_usage(),
+                frequency: cpu.frequency(),
+            })
+            .collect()
+    }
+
     /// Selects the current network values according to the configured interfaces
-    fn select_network(&self) -> HashMap<String, NetworkStatus> {
+    fn select_network(&mut self) -> HashMap<String, NetworkStatus> {
+        let network_totals = self.config.sensors.network_totals;
+        let network_details = self.config.sensors.network_details;
+        let network_packets = self.config.sensors.network_packets;
+        // The Wi-Fi sensors are only registered for the interfaces found wireless at startup
+        let wireless = if self.config.sensors.network.iter().any(|interface| {
+            self.registration_descriptor
+                .has_sensor(Sensor::WifiLinkQuality(interface.clone()))
+        }) {
+            wireless::read()
+        } else {
+            HashMap::new()
+        };
         let mut map = HashMap::new();
         for interface in &self.config.sensors.network {
+            // The drop rates are 0 until the interface has a previous sample
+            let drops = network_packets
+                .then(|| net::drops(Path::new(NET_ROOT), interface))
+                .flatten();
+            let previous_drops = match &drops {
+                Some(drops) => self.net_drops.insert(interface.clone(), drops.clone()),
+                None => self.net_drops.remove(interface),
+            };
+            let (tx_drops, rx_drops) = match (&drops, &previous_drops) {
+                (Some(drops), Some(previous)) => (
+                    self.frequency(drops.tx.saturating_sub(previous.tx)),
+                    self.frequency(drops.rx.saturating_sub(previous.rx)),
+                ),
+                _ => (0.0, 0.0),
+            };
+
             if let Some((_, network_data)) = self.network.iter().find(|n| n.0 == interface) {
+                let addresses = |ipv4: bool| {
+                    let addresses = network_data
+                        .ip_networks()
+                        .iter()
+                        .filter(|network| network.addr.is_ipv4() == ipv4)
+                        .map(|network| network.addr.to_string())
+                        .collect::<Vec<_>>();
+                    (network_details && !addresses.is_empty()).then(|| addresses.join(", "))
+                };
+
+                let wifi = wireless.get(interface);
+
                 map.insert(
                     interface.clone(),
                     NetworkStatus {
                         tx: self.rate(network_data.transmitted()),
                         rx: self.rate(network_data.received()),
+                        tx_packets: network_packets
+                            .then(|| self.frequency(network_data.packets_transmitted())),
+                        rx_packets: network_packets
+                            .then(|| self.frequency(network_data.packets_received())),
+                        tx_errors: network_packets
+                            .then(|| self.frequency(network_data.errors_on_transmitted())),
+                        rx_errors: network_packets
+                            .then(|| self.frequency(network_data.errors_on_received())),
+                        tx_drops: network_packets.then_some(tx_drops),
+                        rx_drops: network_packets.then_some(rx_drops),
+                        tx_total: network_totals.then(|| network_data.total_transmitted()),
+                        rx_total: network_totals.then(|| network_data.total_received()),
+                        wifi_link_quality: wifi.map(|wifi| wifi.link_quality),
+                        wifi_signal: wifi.map(|wifi| wifi.signal_level),
+                        wifi_noise: wifi.and_then(|wifi| wifi.noise_level),
+                        link: network_details
+                            .then(|| on_off(net::link_up(Path::new(NET_ROOT), interface))),
+                        ipv4: addresses(true),
+                        ipv6: addresses(false),
+                        mac: network_details.then(|| network_data.mac_address().to_string()),
                     },
                 );
             };
@@ -129,10 +430,403 @@ impl Daemon {
 
         map
     }
+
+    /// Selects the warning state of the temperatures having a warning threshold
+    fn select_temperature_warning(
+        &mut self,
+        temperatures: &HashMap<String, f32>,
+    ) -> HashMap<String, &'static str> {
+        let mut map = HashMap::new();
+        for (id, temperature) in temperatures {
+            let Some((threshold, hysteresis)) =
+                temperature_warning(self.config.sensors.temperature_thresholds.get(id))
+            else {
+                continue;
+            };
+
+            let active = self.temperature_warnings.entry(id.clone()).or_default();
+            map.insert(
+                id.clone(),
+                on_off(crossed(active, *temperature, threshold, hysteresis)),
+            );
+        }
+
+        map
+    }
+
+    /// Selects the critical state of the temperatures having a critical threshold
+    fn select_temperature_critical(
+        &mut self,
+        temperatures: &HashMap<String, f32>,
+    ) -> HashMap<String, &'static str> {
+        let mut map = HashMap::new();
+        for (id, temperature) in temperatures {
+            let critical = self
+                .components
+                .iter()
+                .find(|c| c.id() == Some(id))
+                .and_then(|c| c.critical());
+            let Some((threshold, hysteresis)) =
+                temperature_critical(self.config.sensors.temperature_thresholds.get(id), critical)
+            else {
+                continue;
+            };
+
+            let active = self.temperature_criticals.entry(id.clone()).or_default();
+            map.insert(
+                id.clone(),
+                on_off(crossed(active, *temperature, threshold, hysteresis)),
+            );
+        }
+
+        map
+    }
+
+    /// Selects the current hardware monitor values according to the configured ids
+    fn select_hwmon(&self, ids: &[String]) -> HashMap<String, f32> {
+        let root = Path::new(&self.config.sensors.hwmon_root);
+        ids.iter()
+            .filter_map(|id| hwmon::read(root, id).map(|value| (id.clone(), value)))
+            .collect()
+    }
+
+    /// Selects the current disk values according to the configured mount points
+    fn select_disk(&self) -> HashMap<String, DiskStatus> {
+        let mut map = HashMap::new();
+        for mount in &self.config.sensors.disk {
+            if let Some(disk) = self
+                .disks
+                .iter()
+                .find(|d| d.mount_point() == Path::new(mount))
+            {
+                let total = disk.total_space();
+                let free = disk.available_space();
+                let used = total.saturating_sub(free);
+                map.insert(
+                    mount_key(mount),
+                    DiskStatus {
+                        usage: if total > 0 {
+                            100.0 * (used as f32 / total as f32)
+                        } else {
+                            0.0
+                        },
+                        used,
+                        free,
+                        inode_usage: statvfs::inodes(Path::new(mount))
+                            .ok()
+                            .and_then(|inodes| inodes.usage()),
+                    },
+                );
+            }
+        }
+
+        map
+    }
+
+    /// Selects the current disk I/O values according to the configured block devices
+    ///
+    /// The rates are computed against the counters read during the previous call. A device
+    /// that was not present during the previous call has no rate yet.
+    fn select_disk_io(&mut self) -> HashMap<String, DiskIoStatus> {
+        let mut map = HashMap::new();
+        if self.config.sensors.disk_io.is_empty() {
+            return map;
+        }
+
+        let disk_stats = diskstats::read();
+        for device in &self.config.sensors.disk_io {
+            if let Some(current) = disk_stats.get(device)
+                && let Some(previous) = self.disk_stats.get(device)
+            {
+                map.insert(
+                    key(device),
+                    DiskIoStatus {
+                        read: self.rate(current.read_bytes.saturating_sub(previous.read_bytes)),
+                        write: self
+                            .rate(current.written_bytes.saturating_sub(previous.written_bytes)),
+                        read_ops: self.frequency(current.reads.saturating_sub(previous.reads)),
+                        write_ops: self.frequency(current.writes.saturating_sub(previous.writes)),
+                    },
+                );
+            }
+        }
+        self.disk_stats = disk_stats;
+
+        map
+    }
+
+    /// Selects the current values of the watched processes
+    fn select_process(&self) -> HashMap<String, ProcessStatus> {
+        let mut map = HashMap::new();
+        for process in &self.config.sensors.process {
+            let mut status = ProcessStatus {
+                running: "OFF",
+                ..Default::default()
+            };
+
+            for pid in self.find_process(process) {
+                if let Some(process) = self.system.process(pid) {
+                    status.running = "ON";
+                    status.cpu_usage += process.cpu_usage();
+                    status.memory += process.memory();
+                }
+            }
+
+            map.insert(key(&process.name), status);
+        }
+
+        map
+    }
+
+    /// Returns the PIDs matching the watched process configuration
+    fn find_process(&self, process: &Process) -> Vec<Pid> {
+        if let Some(pidfile) = &process.pidfile {
+            return std::fs::read_to_string(pidfile)
+                .ok()
+                .and_then(|pid| pid.trim().parse::<Pid>().ok())
+                .into_iter()
+                .collect();
+        }
+
+        if let Some(exe) = &process.exe {
+            return self
+                .system
+                .processes()
+                .iter()
+                .filter(|(_, p)| p.exe() == Some(Path::new(exe)))
+                .map(|(pid, _)| *pid)
+                .collect();
+        }
+
+        let name = process.process_name.as_ref().unwrap_or(&process.name);
+        self.system
+            .processes_by_exact_name(name.as_ref())
+            .map(|p| p.pid())
+            .collect()
+    }
+
+    /// Selects the state of the configured systemd units from their last query
+    fn select_systemd(&self) -> HashMap<String, SystemdUnitStatus> {
+        self.collected()
+            .systemd
+            .iter()
+            .map(|(unit, state)| {
+                (
+                    key(unit),
+                    SystemdUnitStatus {
+                        failed: on_off(state.active_state == "failed"),
+                        active_state: state.active_state.clone(),
+                        sub_state: state.sub_state.clone(),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Selects the current state of the configured software RAID arrays
+    fn select_raid(&self) -> HashMap<String, RaidStatus> {
+        if self.config.sensors.raid.is_empty() {
+            return HashMap::new();
+        }
+
+        let arrays = mdstat::read();
+        self.config
+            .sensors
+            .raid
+            .iter()
+            .filter_map(|name| {
+                let array = arrays.get(name)?;
+                Some((
+                    key(name),
+                    RaidStatus {
+                        problem: on_off(!array.active || array.degraded),
+                        sync_progress: array.sync_progress.unwrap_or(100.0),
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    /// Selects the current state of the configured ZFS pools
+    fn select_zfs(&self) -> HashMap<String, ZfsPoolStatus> {
+        self.collected()
+            .zfs
+            .iter()
+            .map(|(name, pool)| {
+                (
+                    key(name),
+                    ZfsPoolStatus {
+                        problem: on_off(pool.health != "ONLINE"),
+                        health: pool.health.clone(),
+                        capacity: pool.capacity,
+                        scan_progress: pool.scan_progress.unwrap_or(100.0),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Selects the reboot required state found by the last check
+    fn select_reboot_required(&self) -> Option<&'static str> {
+        if !self.config.sensors.reboot_required {
+            return None;
+        }
+
+        self.collected().reboot_required.map(on_off)
+    }
+
+    /// Selects the number of pending updates found by the last check
+    fn select_pending_updates(&self) -> Option<u64> {
+        self.config.sensors.updates.as_ref()?;
+
+        self.collected().pending_updates
+    }
+
+    /// Selects the values of the custom commands from their last run, `null` until they ran once
+    fn select_command(&self) -> HashMap<String, Value> {
+        let collected = self.collected();
+        self.config
+            .sensors
+            .command
+            .iter()
+            .map(|command| {
+                let value = collected.commands.get(&command.name).cloned();
+                (key(&command.name), value.unwrap_or(Value::Null))
+            })
+            .collect()
+    }
+
+    /// Selects the values of the file sensors, or `null` if a file cannot be read
+    fn select_file(&self) -> HashMap<String, Value> {
+        let mut map = HashMap::new();
+        for file in &self.config.sensors.file {
+            let value = file::read(&file.path, file.scale).unwrap_or_else(|e| {
+                warn!("File sensor {} failed: {e}", file.name);
+                Value::Null
+            });
+
+            map.insert(key(&file.name), value);
+        }
+
+        map
+    }
+
+    /// Returns the values collected by the background tasks
+    fn collected(&self) -> MutexGuard<'_, Collected> {
+        self.collected
+            .lock()
+            .expect("Collected values lock poisoned")
+    }
+
+    /// Spawns the background tasks refreshing the slow sensors
+    ///
+    /// The tasks are stopped when the daemon is dropped
+    pub fn spawn_collectors(&mut self) {
+        for command in self.config.sensors.command.clone() {
+            let interval =
+                Duration::from_secs(command.interval.unwrap_or(self.config.mqtt.update_period));
+            let collected = self.collected.clone();
+            self.collectors.spawn(async move {
+                loop {
+                    let value = run_command(&command).await;
+                    collected
+                        .lock()
+                        .expect("Collected values lock poisoned")
+                        .commands
+                        .insert(command.name.clone(), value);
+                    sleep(interval).await;
+                }
+            });
+        }
+
+        if !self.config.sensors.systemd_units.is_empty() {
+            let units = self.config.sensors.systemd_units.clone();
+            let interval = Duration::from_secs(self.config.mqtt.update_period);
+            let collected = self.collected.clone();
+            self.collectors.spawn(async move {
+                loop {
+                    let states = systemd::read(systemd::SYSTEMCTL, &units).await;
+                    collected
+                        .lock()
+                        .expect("Collected values lock poisoned")
+                        .systemd = states;
+                    sleep(interval).await;
+                }
+            });
+        }
+
+        if !self.config.sensors.zfs_pools.is_empty() {
+            let pools = self.config.sensors.zfs_pools.clone();
+            let interval = Duration::from_secs(self.config.mqtt.update_period);
+            let collected = self.collected.clone();
+            self.collectors.spawn(async move {
+                loop {
+                    let states = zfs::read(zfs::ZPOOL, &pools).await;
+                    collected
+                        .lock()
+                        .expect("Collected values lock poisoned")
+                        .zfs = states;
+                    sleep(interval).await;
+                }
+            });
+        }
+
+        let pending_updates = self.config.sensors.updates.clone();
+        let reboot_required = self.config.sensors.reboot_required;
+        if pending_updates.is_some() || reboot_required {
+            let interval = Duration::from_secs(
+                pending_updates
+                    .as_ref()
+                    .map_or(DEFAULT_UPDATES_INTERVAL, |config| config.interval),
+            );
+            let collected = self.collected.clone();
+            self.collectors.spawn(async move {
+                loop {
+                    if reboot_required {
+                        trace!("Checking if a reboot is required");
+                        let required = updates::reboot_required(
+                            Path::new(REBOOT_REQUIRED_PATH),
+                            Path::new(MODULES_ROOT),
+                            Path::new(BOOT_ROOT),
+                            &updates::running_kernel().unwrap_or_default(),
+                        );
+                        collected
+                            .lock()
+                            .expect("Collected values lock poisoned")
+                            .reboot_required = Some(required);
+                    }
+
+                    if let Some(config) = &pending_updates {
+                        trace!("Checking the pending updates");
+                        let count = command::run(
+                            updates::command(&config.package_manager),
+                            Duration::from_secs(config.timeout),
+                        )
+                        .await
+                        .map(|output| updates::count(&config.package_manager, &output))
+                        .inspect_err(|e| warn!("Cannot check the pending updates: {e}"))
+                        .ok();
+
+                        collected
+                            .lock()
+                            .expect("Collected values lock poisoned")
+                            .pending_updates = count;
+                    }
+
+                    sleep(interval).await;
+                }
+            });
+        }
+    }
+
     fn rate(&self, diff: u64) -> f64 {
         (diff / self.config.mqtt.update_period) as f64 / 1024.0
     }
 
+    fn frequency(&self, diff: u64) -> f64 {
+        diff as f64 / self.config.mqtt.update_period as f64
+    }
+
     /// Registers the configured sensors in the descriptor
     pub fn register_sensors(&mut self) {
         self.registration_descriptor
@@ -141,6 +835,43 @@ impl Daemon {
         self.registration_descriptor
             .add_component(Sensor::MemoryUsage);
 
+        for memory in &self.config.sensors.memory {
+            debug!("Adding memory sensor {memory:?}");
+            self.registration_descriptor.add_component(match memory {
+                MemorySensor::Swap => Sensor::SwapUsage,
+                MemorySensor::Available => Sensor::MemoryAvailable,
+                MemorySensor::Cached => Sensor::MemoryCached,
+                MemorySensor::Buffers => Sensor::MemoryBuffers,
+            });
+        }
+
+        if self.config.sensors.per_cpu {
+            for core in 0..self.system.cpus().len() {
+                debug!("Adding logical core {core}");
+                self.registration_descriptor
+                    .add_component(Sensor::CpuCoreUsage(core));
+                self.registration_descriptor
+                    .add_component(Sensor::CpuCoreFrequency(core));
+            }
+        }
+
+        self.registration_descriptor
+            .add_component(Sensor::LoadAverage1);
+        self.registration_descriptor
+            .add_component(Sensor::LoadAverage5);
+        self.registration_descriptor
+            .add_component(Sensor::LoadAverage15);
+        self.registration_descriptor.add_component(Sensor::Uptime);
+
+        if self.config.sensors.reboot_required {
+            self.registration_descriptor
+                .add_component(Sensor::RebootRequired);
+        }
+        if self.config.sensors.updates.is_some() {
+            self.registration_descriptor
+                .add_component(Sensor::PendingUpdates);
+        }
+
         for id in &self.config.sensors.temperature {
             debug!("Adding temperature {id}");
 
@@ -153,57 +884,294 @@ impl Daemon {
                             id.clone(),
                             component.label().to_string(),
                         ));
+                    let threshold = self.config.sensors.temperature_thresholds.get(id);
+                    if temperature_warning(threshold).is_some() {
+                        self.registration_descriptor
+                            .add_component(Sensor::TemperatureWarning(
+                                id.clone(),
+                                component.label().to_string(),
+                            ));
+                    }
+                    if temperature_critical(threshold, component.critical()).is_some() {
+                        self.registration_descriptor
+                            .add_component(Sensor::TemperatureCritical(
+                                id.clone(),
+                                component.label().to_string(),
+                            ));
+                    }
                     break;
                 }
             }
         }
 
+        for process in &self.config.sensors.process {
+            debug!("Adding process {}", process.name);
+            self.registration_descriptor
+                .add_component(Sensor::ProcessRunning(process.name.clone()));
+            self.registration_descriptor
+                .add_component(Sensor::ProcessCpuUsage(process.name.clone()));
+            self.registration_descriptor
+                .add_component(Sensor::ProcessMemory(process.name.clone()));
+        }
+
+        for unit in &self.config.sensors.systemd_units {
+            debug!("Adding systemd unit {unit}");
+            self.registration_descriptor
+                .add_component(Sensor::UnitActiveState(unit.clone()));
+            self.registration_descriptor
+                .add_component(Sensor::UnitSubState(unit.clone()));
+            self.registration_descriptor
+                .add_component(Sensor::UnitFailed(unit.clone()));
+        }
+
+        for array in &self.config.sensors.raid {
+            debug!("Adding RAID array {array}");
+            self.registration_descriptor
+                .add_component(Sensor::RaidProblem(array.clone()));
+            self.registration_descriptor
+                .add_component(Sensor::RaidSyncProgress(array.clone()));
+        }
+
+        for pool in &self.config.sensors.zfs_pools {
+            debug!("Adding ZFS pool {pool}");
+            self.registration_descriptor
+                .add_component(Sensor::ZfsProblem(pool.clone()));
+            self.registration_descriptor
+                .add_component(Sensor::ZfsHealth(pool.clone()));
+            self.registration_descriptor
+                .add_component(Sensor::ZfsCapacity(pool.clone()));
+            self.registration_descriptor
+                .add_component(Sensor::ZfsScanProgress(pool.clone()));
+        }
+
+        for command in &self.config.sensors.command {
+            debug!("Adding command {}", command.name);
+            self.registration_descriptor.add_component(Sensor::Command {
+                name: command.name.clone(),
+                unit: command.unit.clone(),
+                device_class: command.device_class.clone(),
+            });
+        }
+
+        for file in &self.config.sensors.file {
+            debug!("Adding file {}", file.name);
+            self.registration_descriptor.add_component(Sensor::File {
+                name: file.name.clone(),
+                unit: file.unit.clone(),
+                device_class: file.device_class.clone(),
+            });
+        }
+
+        if self.config.sensors.power_supply {
+            let state = power_supply::read(Path::new(&self.config.sensors.power_supply_root));
+            if state.battery_level.is_some() {
+                debug!("Adding battery");
+                self.registration_descriptor
+                    .add_component(Sensor::BatteryLevel);
+                self.registration_descriptor
+                    .add_component(Sensor::BatteryCharging);
+            }
+            if state.ac_connected.is_some() {
+                debug!("Adding AC adapter");
+                self.registration_descriptor
+                    .add_component(Sensor::AcConnected);
+            }
+        }
+
+        if self.config.sensors.pressure {
+            for resource in pressure::RESOURCES {
+                let Some(pressure) = pressure::read(Path::new(PRESSURE_ROOT), resource) else {
+                    debug!("No pressure information for {resource}");
+                    continue;
+                };
+
+                debug!("Adding pressure of {resource}");
+                let mut fields = vec!["some_avg10", "some_avg60"];
+                if pressure.full_avg10.is_some() {
+                    fields.extend(["full_avg10", "full_avg60"]);
+                }
+                for field in fields {
+                    self.registration_descriptor
+                        .add_component(Sensor::Pressure(resource.to_string(), field.to_string()));
+                }
+            }
+        }
+
+        let hwmon_root = Path::new(&self.config.sensors.hwmon_root);
+        for id in &self.config.sensors.fan {
+            if hwmon::read(hwmon_root, id).is_some() {
+                debug!("Adding fan {id}");
+                self.registration_descriptor
+                    .add_component(Sensor::Fan(id.clone(), hwmon::label(hwmon_root, id)));
+            }
+        }
+
+        for id in &self.config.sensors.voltage {
+            if hwmon::read(hwmon_root, id).is_some() {
+                debug!("Adding voltage {id}");
+                self.registration_descriptor
+                    .add_component(Sensor::Voltage(id.clone(), hwmon::label(hwmon_root, id)));
+            }
+        }
+
+        let wireless = wireless::read();
         for interface in &self.config.sensors.network {
             debug!("Adding interface {interface}");
             self.registration_descriptor
                 .add_component(Sensor::NetTx(interface.clone()));
             self.registration_descriptor
                 .add_component(Sensor::NetRx(interface.clone()));
+            if self.config.sensors.network_packets {
+                for sensor in [
+                    Sensor::NetTxPackets,
+                    Sensor::NetRxPackets,
+                    Sensor::NetTxErrors,
+                    Sensor::NetRxErrors,
+                    Sensor::NetTxDrops,
+                    Sensor::NetRxDrops,
+                ] {
+                    self.registration_descriptor
+                        .add_component(sensor(interface.clone()));
+                }
+            }
+            if self.config.sensors.network_totals {
+                self.registration_descriptor
+                    .add_component(Sensor::NetTxTotal(interface.clone()));
+                self.registration_descriptor
+                    .add_component(Sensor::NetRxTotal(interface.clone()));
+            }
+            if net::is_wireless(Path::new(NET_ROOT), interface) {
+                debug!("Adding Wi-Fi sensors for {interface}");
+                self.registration_descriptor
+                    .add_component(Sensor::WifiLinkQuality(interface.clone()));
+                self.registration_descriptor
+                    .add_component(Sensor::WifiSignal(interface.clone()));
+                // Many drivers don't report the noise level
+                if wireless
+                    .get(interface)
+                    .is_some_and(|wifi| wifi.noise_level.is_some())
+                {
+                    self.registration_descriptor
+                        .add_component(Sensor::WifiNoise(interface.clone()));
+                }
+            }
+            if self.config.sensors.network_details {
+                self.registration_descriptor
+                    .add_component(Sensor::NetLink(interface.clone()));
+                self.registration_descriptor
+                    .add_component(Sensor::NetIpv4(interface.clone()));
+                self.registration_descriptor
+                    .add_component(Sensor::NetIpv6(interface.clone()));
+                self.registration_descriptor
+                    .add_component(Sensor::NetMac(interface.clone()));
+            }
+        }
+
+        for mount in &self.config.sensors.disk {
+            debug!("Adding mount point {mount}");
+            self.registration_descriptor
+                .add_component(Sensor::DiskUsage(mount.clone()));
+            self.registration_descriptor
+                .add_component(Sensor::DiskUsed(mount.clone()));
+            self.registration_descriptor
+                .add_component(Sensor::DiskFree(mount.clone()));
+            self.registration_descriptor
+                .add_component(Sensor::DiskInodeUsage(mount.clone()));
+        }
+
+        for device in &self.config.sensors.disk_io {
+            debug!("Adding block device {device}");
+            self.registration_descriptor
+                .add_component(Sensor::DiskRead(device.clone()));
+            self.registration_descriptor
+                .add_component(Sensor::DiskWrite(device.clone()));
+            self.registration_descriptor
+                .add_component(Sensor::DiskReadOps(device.clone()));
+            self.registration_descriptor
+                .add_component(Sensor::DiskWriteOps(device.clone()));
         }
     }
 
     /// Runs the main loop that periodically sends the MQTT events
     pub async fn run(self: &mut Daemon) {
         self.register_sensors();
+        self.spawn_collectors();
 
         let (client, mut event_loop) = AsyncClient::new(self.mqtt_config.clone(), 1);
+        let birth_topic =
+            RegistrationDescriptor::birth_topic(&self.config.mqtt.registration_prefix);
+        let (registration_sender, registration_receiver) = mpsc::channel(1);
 
         task::spawn(async move {
-            while let Ok(notification) = event_loop.poll().await {
+            let mut reconnect_delay = RECONNECT_DELAY_MIN;
+            loop {
+                let notification = match event_loop.poll().await {
+                    Ok(notification) => notification,
+                    Err(e) => {
+                        warn!("MQTT connection error: {e}, reconnecting in {reconnect_delay:?}");
+                        sleep(reconnect_delay).await;
+                        reconnect_delay = (reconnect_delay * 2).min(RECONNECT_DELAY_MAX);
+                        continue;
+                    }
+                };
                 trace!("MQTT notification received: {notification:?}");
+
+                let register = match notification {
+                    Event::Incoming(Packet::ConnAck(_)) => {
+                        info!("Connected to the MQTT broker");
+                        reconnect_delay = RECONNECT_DELAY_MIN;
+                        true
+                    }
+                    Event::Incoming(Packet::Publish(publish)) => {
+                        let online =
+                            publish.topic == birth_topic && publish.payload.as_ref() == b"online";
+                        if online {
+                            debug!("Home Assistant is online");
+                        }
+                        online
+                    }
+                    _ => false,
+                };
+
+                if register {
+                    // A registration is already pending if the channel is full
+                    let _ = registration_sender.try_send(());
+                }
             }
         });
 
-        self.main_loop(client).await.unwrap_or_else(|e| {
-            error!("MQTT main loop failed: {e}");
-        });
+        self.main_loop(client, registration_receiver)
+            .await
+            .unwrap_or_else(|e| {
+                error!("MQTT main loop failed: {e}");
+            });
     }
 
     /// Single iteration of the main loop
-    async fn main_loop(self: &mut Daemon, client: AsyncClient) -> Result<(), Box<dyn Error>> {
-        let mut cycles_counter = 0;
-        let expire_cycles = 60 / self.config.mqtt.update_period - 1;
+    ///
+    /// The registration is published each time the daemon connects to the broker, and each time
+    /// Home Assistant sends its birth message
+    async fn main_loop(
+        self: &mut Daemon,
+        client: AsyncClient,
+        mut registration_receiver: mpsc::Receiver<()>,
+    ) -> Result<(), Box<dyn Error>> {
         let sleep_period = std::time::Duration::from_secs(self.config.mqtt.update_period);
         let mut terminal_signal = tokio::signal::unix::signal(SignalKind::terminate())?;
         let topic = self.registration_descriptor.state_topic().to_string();
-
-        self.publish_registration(&client).await?;
-        sleep(std::time::Duration::from_secs(1)).await;
+        let birth_topic =
+            RegistrationDescriptor::birth_topic(&self.config.mqtt.registration_prefix);
 
         loop {
-            cycles_counter = (cycles_counter + 1) % expire_cycles;
-            if cycles_counter == 0 {
-                self.publish_registration(&client).await?;
-            }
-
             self.publish_update(&client, &topic).await?;
             tokio::select! {
                 _ = sleep(sleep_period) => {},
+                Some(()) = registration_receiver.recv() => {
+                    // The subscriptions are lost with the session when the connection drops
+                    client.subscribe(&birth_topic, QoS::AtLeastOnce).await?;
+                    self.publish_registration(&client).await?;
+                    sleep(std::time::Duration::from_secs(1)).await;
+                },
                 _ = tokio::signal::ctrl_c() => {
                     debug!("Ctrl-C received");
                     break;
@@ -215,7 +1183,8 @@ impl Daemon {
             }
         }
 
-        Daemon::publish(&client, topic, &StatusMessage::off().to_string()).await?;
+        self.publish_availability(&client, PAYLOAD_NOT_AVAILABLE)
+            .await?;
 
         sleep(std::time::Duration::from_secs(1)).await;
 
@@ -228,7 +1197,9 @@ impl Daemon {
         client: &AsyncClient,
         topic: &str,
     ) -> Result<(), Box<dyn Error>> {
-        Daemon::publish(client, topic, &self.update_data().to_string()).await?;
+        let data = self.update_data().to_string();
+        let mqtt = &self.config.mqtt;
+        Daemon::publish(client, topic, &data, mqtt.state_qos, mqtt.state_retain).await?;
 
         Ok(())
     }
@@ -246,29 +1217,141 @@ impl Daemon {
             client,
             descriptor.discovery_topic(prefix),
             &descriptor.to_string(),
+            self.config.mqtt.discovery_qos,
+            self.config.mqtt.discovery_retain,
         )
-        .await
+        .await?;
+
+        self.publish_availability(client, PAYLOAD_AVAILABLE).await
+    }
+
+    /// Publishes the availability of the device, retained so that it is known after a restart of
+    /// Home Assistant
+    async fn publish_availability(
+        &self,
+        client: &AsyncClient,
+        payload: &str,
+    ) -> Result<(), ClientError> {
+        let topic = self.registration_descriptor.availability_topic();
+
+        Daemon::publish(client, topic, payload, QoS::AtLeastOnce, true).await
     }
 
     // Publish a message to MQTT
-    async fn publish<S>(client: &AsyncClient, topic: S, data: &str) -> Result<(), ClientError>
+    async fn publish<S>(
+        client: &AsyncClient,
+        topic: S,
+        data: &str,
+        qos: QoS,
+        retain: bool,
+    ) -> Result<(), ClientError>
     where
         S: Into<String> + std::fmt::Display,
     {
         debug!("Publishing to topic {topic} : {data}");
-        client.publish(topic, QoS::AtLeastOnce, false, data).await
+        client.publish(topic, qos, retain, data).await
     }
 }
 
+/// Runs a custom command and returns its value, or `null` if it failed
+async fn run_command(command: &CommandSensor) -> Value {
+    trace!("Running command {}", command.command);
+    command::run(&command.command, Duration::from_secs(command.timeout))
+        .await
+        .and_then(|output| value::parse(&output, command.json_path.as_deref()))
+        .unwrap_or_else(|e| {
+            warn!("Command sensor {} failed: {e}", command.name);
+            Value::Null
+        })
+}
+
+/// Builds the TLS configuration of the transport
+///
+/// Fails if the certificates or the key cannot be loaded
+fn tls_configuration(tls: &Tls) -> Result<TlsConfiguration, Box<dyn Error>> {
+    let config = tls::client_config(tls).map_err(|err| format!("Cannot configure TLS: {err}"))?;
+
+    Ok(TlsConfiguration::Rustls(Arc::new(config)))
+}
+
+/// Returns the warning threshold and the hysteresis of a temperature, if it has one
+fn temperature_warning(threshold: Option<&TemperatureThreshold>) -> Option<(f32, f32)> {
+    let threshold = threshold.cloned().unwrap_or_default();
+
+    threshold.warning.map(|value| (value, threshold.hysteresis))
+}
+
+/// Returns the critical threshold and the hysteresis of a temperature, if it has one
+///
+/// The configured critical temperature has precedence over the one reported by the sensor.
+fn temperature_critical(
+    threshold: Option<&TemperatureThreshold>,
+    sensor_critical: Option<f32>,
+) -> Option<(f32, f32)> {
+    let threshold = threshold.cloned().unwrap_or_default();
+
+    threshold
+        .critical
+        .or(sensor_critical)
+        .map(|value| (value, threshold.hysteresis))
+}
+
+/// Updates the state of a temperature problem and returns it
+///
+/// A problem is raised when the temperature reaches the threshold, and cleared when it goes
+/// below the threshold minus the hysteresis.
+fn crossed(active: &mut bool, temperature: f32, threshold: f32, hysteresis: f32) -> bool {
+    *active = if *active {
+        temperature >= threshold - hysteresis
+    } else {
+        temperature >= threshold
+    };
+
+    *active
+}
+
+/// Converts a boolean to the payload of a binary sensor
+fn on_off(value: bool) -> &'static str {
+    if value { "ON" } else { "OFF" }
+}
+
+/// Formats a UNIX timestamp in seconds as a RFC 3339 UTC date, as expected by Home Assistant
+fn timestamp(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let seconds_of_day = secs % 86400;
+
+    // Civil date from the number of days since the epoch, see
+    // <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}+00:00",
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tungstenite::Message;
+    use tungstenite::handshake::server::{Request, Response};
 
     #[test]
     fn test_rate() {
         let config = Configuration::load("conf/mqtt-system-monitor.conf")
             .expect("Failed to load default config");
-        let mut daemon = Daemon::new(config);
+        let mut daemon = Daemon::new(config).expect("Cannot create daemon");
 
         daemon.config.mqtt.update_period = 1;
         assert_eq!(daemon.rate(1024), 1.0);
@@ -277,4 +1360,249 @@ mod tests {
         // The total received was increased by 20 KiBytes, divided by the update of 10 is 2 KiBytes/s
         assert_eq!(daemon.rate(2 * 1024 * 10), 2.0);
     }
+
+    #[test]
+    fn test_disk_io_new_device() {
+        let mut config = Configuration::load("conf/mqtt-system-monitor.conf")
+            .expect("Failed to load default config");
+        config.sensors.disk_io = diskstats::read().into_keys().take(1).collect();
+        let Some(device) = config.sensors.disk_io.first().cloned() else {
+            return;
+        };
+        let mut daemon = Daemon::new(config).expect("Cannot create daemon");
+
+        // The device appeared after the startup, its counters are not compared against zero
+        daemon.disk_stats.clear();
+        assert!(daemon.select_disk_io().is_empty());
+        assert!(daemon.select_disk_io().contains_key(&key(&device)));
+    }
+
+    #[test]
+    fn test_frequency() {
+        let config = Configuration::load("conf/mqtt-system-monitor.conf")
+            .expect("Failed to load default config");
+        let mut daemon = Daemon::new(config).expect("Cannot create daemon");
+
+        daemon.config.mqtt.update_period = 10;
+        // 25 operations during 10 seconds
+        assert_eq!(daemon.frequency(25), 2.5);
+    }
+
+    #[test]
+    fn test_temperature_problem() {
+        let mut config = Configuration::load("conf/mqtt-system-monitor.conf")
+            .expect("Failed to load default config");
+        config.sensors.temperature_thresholds = toml::from_str(
+            r#"
+            [fake_1]
+            warning = 70
+            critical = 90
+            "#,
+        )
+        .expect("Invalid thresholds");
+        let mut daemon = Daemon::new(config).expect("Cannot create daemon");
+
+        let mut problem = |temperature: f32| {
+            let temperatures = HashMap::from([
+                ("fake_1".to_string(), temperature),
+                ("no_threshold".to_string(), temperature),
+            ]);
+            let warnings = daemon.select_temperature_warning(&temperatures);
+            let criticals = daemon.select_temperature_critical(&temperatures);
+            assert!(!warnings.contains_key("no_threshold"));
+            assert!(!criticals.contains_key("no_threshold"));
+            (warnings["fake_1"], criticals["fake_1"])
+        };
+
+        assert_eq!(problem(69.0), ("OFF", "OFF"));
+        assert_eq!(problem(70.0), ("ON", "OFF"));
+        // Each problem stays until the temperature goes below its threshold minus the hysteresis
+        assert_eq!(problem(68.5), ("ON", "OFF"));
+        assert_eq!(problem(90.0), ("ON", "ON"));
+        assert_eq!(problem(88.5), ("ON", "ON"));
+        assert_eq!(problem(87.9), ("ON", "OFF"));
+        assert_eq!(problem(89.0), ("ON", "OFF"));
+        assert_eq!(problem(67.9), ("OFF", "OFF"));
+        assert_eq!(problem(69.0), ("OFF", "OFF"));
+    }
+
+    #[test]
+    fn test_temperature_threshold() {
+        let threshold = TemperatureThreshold {
+            warning: Some(70.0),
+            critical: Some(90.0),
+            hysteresis: 5.0,
+        };
+
+        assert_eq!(temperature_warning(None), None);
+        assert_eq!(temperature_warning(Some(&threshold)), Some((70.0, 5.0)));
+        assert_eq!(temperature_critical(None, None), None);
+        assert_eq!(temperature_critical(None, Some(100.0)), Some((100.0, 2.0)));
+        assert_eq!(
+            temperature_critical(Some(&threshold), Some(100.0)),
+            Some((90.0, 5.0))
+        );
+    }
+
+    /// Test that invalid TLS settings are reported instead of panicking
+    #[test]
+    fn test_tls_error() {
+        let mut config = Configuration::load("conf/mqtt-system-monitor.conf")
+            .expect("Failed to load default config");
+        config.mqtt.tls = Some(Tls {
+            ca_file: Some(String::from("/nonexistent/ca.crt")),
+            ..Default::default()
+        });
+
+        let error = Daemon::new(config)
+            .err()
+            .expect("Invalid TLS settings accepted");
+        assert!(error.to_string().starts_with("Cannot configure TLS"));
+    }
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(timestamp(0), "1970-01-01T00:00:00+00:00");
+        assert_eq!(timestamp(951782400), "2000-02-29T00:00:00+00:00");
+        assert_eq!(timestamp(1754745296), "2025-08-09T13:14:56+00:00");
+    }
+
+    /// Connects to a fake broker through the WebSocket transport
+    #[tokio::test]
+    // The error type of the handshake callback is imposed by tungstenite
+    #[allow(clippy::result_large_err)]
+    async fn test_websocket() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("Cannot listen");
+        let port = listener.local_addr().expect("No local address").port();
+
+        let broker = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("No connection");
+            let mut path = String::new();
+            let mut socket =
+                tungstenite::accept_hdr(stream, |request: &Request, mut response: Response| {
+                    path = request.uri().path().to_string();
+                    response
+                        .headers_mut()
+                        .insert("Sec-WebSocket-Protocol", "mqtt".parse().unwrap());
+                    Ok(response)
+                })
+                .expect("WebSocket handshake failed");
+            let connect = socket.read().expect("No CONNECT packet").into_data();
+            // CONNACK, no session present, connection accepted
+            socket
+                .send(Message::Binary(vec![0x20, 0x02, 0x00, 0x00]))
+                .expect("Cannot send CONNACK");
+            (path, connect[0])
+        });
+
+        let mut config = Configuration::load("conf/mqtt-system-monitor.conf")
+            .expect("Failed to load default config");
+        config.mqtt.host = String::from("127.0.0.1");
+        config.mqtt.port = port;
+        config.mqtt.transport = configuration::Transport::Ws;
+        config.mqtt.path = String::from("/ws");
+        let daemon = Daemon::new(config).expect("Cannot create daemon");
+
+        let (_client, mut eventloop) = AsyncClient::new(daemon.mqtt_config.clone(), 10);
+        let event = eventloop.poll().await;
+        let (path, packet_type) = broker.join().expect("Fake broker failed");
+
+        assert_eq!(path, "/ws");
+        // CONNECT packet type
+        assert_eq!(packet_type, 0x10);
+        assert!(matches!(event, Ok(Event::Incoming(Packet::ConnAck(_)))));
+    }
+
+    /// Reads an MQTT packet and returns its first byte and its body
+    fn read_packet(stream: &mut std::net::TcpStream) -> (u8, Vec<u8>) {
+        use std::io::Read;
+
+        let mut byte = [0u8];
+        stream.read_exact(&mut byte).expect("No packet");
+        let packet_type = byte[0];
+
+        let mut length = 0;
+        for shift in (0..28).step_by(7) {
+            stream.read_exact(&mut byte).expect("No packet length");
+            length |= ((byte[0] & 0x7f) as usize) << shift;
+            if byte[0] & 0x80 == 0 {
+                break;
+            }
+        }
+
+        let mut body = vec![0; length];
+        stream.read_exact(&mut body).expect("No packet body");
+        (packet_type, body)
+    }
+
+    /// Returns the topic of a PUBLISH or SUBSCRIBE packet
+    fn packet_topic(body: &[u8]) -> String {
+        let length = u16::from_be_bytes([body[0], body[1]]) as usize;
+        String::from_utf8_lossy(&body[2..2 + length]).to_string()
+    }
+
+    /// Drops the connection to a fake broker and checks that the daemon registers again
+    #[tokio::test]
+    async fn test_reconnection() {
+        use std::io::Write;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("Cannot listen");
+        let port = listener.local_addr().expect("No local address").port();
+
+        let mut config = Configuration::load("conf/mqtt-system-monitor.conf")
+            .expect("Failed to load default config");
+        config.mqtt.host = String::from("127.0.0.1");
+        config.mqtt.port = port;
+        let birth_topic = RegistrationDescriptor::birth_topic(&config.mqtt.registration_prefix);
+        let mut daemon = Daemon::new(config).expect("Cannot create daemon");
+        let availability_topic = daemon
+            .registration_descriptor()
+            .availability_topic()
+            .to_string();
+
+        let broker = std::thread::spawn(move || {
+            let mut subscriptions = Vec::new();
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().expect("No connection");
+                stream
+                    .set_read_timeout(Some(Duration::from_secs(10)))
+                    .expect("Cannot set the timeout");
+                assert_eq!(read_packet(&mut stream).0, 0x10);
+                // CONNACK, no session present, connection accepted
+                stream
+                    .write_all(&[0x20, 0x02, 0x00, 0x00])
+                    .expect("Cannot send CONNACK");
+
+                // The connection is dropped once the device subscribed again and is available,
+                // the messages that were not acknowledged may be sent again before
+                let subscribed = subscriptions.len();
+                loop {
+                    let (packet_type, body) = read_packet(&mut stream);
+                    match packet_type >> 4 {
+                        // SUBSCRIBE, the topic follows the packet identifier
+                        8 => subscriptions.push(packet_topic(&body[2..])),
+                        3 if packet_topic(&body) == availability_topic
+                            && body.ends_with(PAYLOAD_AVAILABLE.as_bytes())
+                            && subscriptions.len() > subscribed =>
+                        {
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            subscriptions
+        });
+
+        let running = task::spawn(async move { daemon.run().await });
+        let broker = task::spawn_blocking(move || broker.join());
+        let subscriptions = tokio::time::timeout(Duration::from_secs(10), broker)
+            .await
+            .expect("The daemon did not reconnect")
+            .expect("Fake broker task failed")
+            .expect("Fake broker failed");
+        running.abort();
+
+        assert_eq!(subscriptions, vec![birth_topic.clone(), birth_topic]);
+    }
 }
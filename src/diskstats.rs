@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+/// Path of the block device statistics provided by the kernel
+pub const DISKSTATS_PATH: &str = "/proc/diskstats";
+
+/// Size of a sector as reported in `/proc/diskstats`, regardless of the device
+const SECTOR_SIZE: u64 = 512;
+
+/// Cumulative I/O counters of a block device
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DiskStats {
+    /// Number of completed reads
+    pub reads: u64,
+
+    /// Number of bytes read
+    pub read_bytes: u64,
+
+    /// Number of completed writes
+    pub writes: u64,
+
+    /// Number of bytes written
+    pub written_bytes: u64,
+}
+
+/// Reads the counters of all the block devices
+///
+/// Returns an empty map if the statistics are not available
+pub fn read() -> HashMap<String, DiskStats> {
+    std::fs::read_to_string(DISKSTATS_PATH)
+        .map(|content| parse(&content))
+        .unwrap_or_default()
+}
+
+/// Parses the content of `/proc/diskstats`
+///
+/// See <https://www.kernel.org/doc/Documentation/ABI/testing/procfs-diskstats> for the format
+///
+/// ## Example
+///
+/// ```
+/// use mqtt_system_monitor::diskstats;
+///
+/// let stats = diskstats::parse("   8       0 sda 10 0 20 0 30 0 40 0 0 0 0");
+///
+/// assert_eq!(stats["sda"].reads, 10);
+/// assert_eq!(stats["sda"].read_bytes, 20 * 512);
+/// ```
+pub fn parse(content: &str) -> HashMap<String, DiskStats> {
+    let mut map = HashMap::new();
+
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 10 {
+            continue;
+        }
+
+        let counter = |index: usize| fields[index].parse::<u64>().unwrap_or_default();
+
+        map.insert(
+            fields[2].to_string(),
+            DiskStats {
+                reads: counter(3),
+                read_bytes: counter(5) * SECTOR_SIZE,
+                writes: counter(7),
+                written_bytes: counter(9) * SECTOR_SIZE,
+            },
+        );
+    }
+
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let content = "
+   8       0 sda 262690 89183 17298846 67093 566349 346575 38218384 589587 0 256476 702219 0 0 0 0 48036 45538
+   8       1 sda1 262555 89183 17290518 67062 566349 346575 38218384 589587 0 256452 656649 0 0 0 0 0 0
+ 253       0 dm-0 11 0 88 2 0 0 0 0 0 4 2
+ invalid line
+";
+        let stats = parse(content);
+
+        assert_eq!(stats.len(), 3);
+        assert_eq!(
+            stats["sda"],
+            DiskStats {
+                reads: 262690,
+                read_bytes: 17298846 * 512,
+                writes: 566349,
+                written_bytes: 38218384 * 512,
+            }
+        );
+        assert_eq!(stats["dm-0"].reads, 11);
+        assert_eq!(stats["dm-0"].written_bytes, 0);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::value;
+use serde_json::Value;
+use std::error::Error;
+use std::path::{Component, Path, PathBuf};
+
+/// Reads the value of a file sensor
+///
+/// The path can contain `*` and `?` wildcards, in which case the first matching file is read.
+/// Numbers are multiplied by `scale`, other values are returned as strings.
+///
+/// ## Example
+///
+/// ```no_run
+/// use mqtt_system_monitor::file;
+///
+/// let level = file::read("/sys/class/power_supply/BAT*/capacity", 1.0).expect("No battery");
+/// ```
+pub fn read(pattern: &str, scale: f64) -> Result<Value, Box<dyn Error>> {
+    let path = resolve(pattern).ok_or_else(|| format!("No file matches {pattern}"))?;
+    let value = value::parse(&std::fs::read_to_string(path)?, None)?;
+
+    Ok(match value.as_f64() {
+        Some(number) => Value::from(number * scale),
+        None => value,
+    })
+}
+
+/// Returns the first existing path matching the pattern, in alphabetical order
+pub fn resolve(pattern: &str) -> Option<PathBuf> {
+    let mut candidates = vec![PathBuf::new()];
+
+    for component in Path::new(pattern).components() {
+        let name = match component {
+            Component::Normal(name) => name.to_string_lossy(),
+            other => {
+                candidates.iter_mut().for_each(|c| c.push(other));
+                continue;
+            }
+        };
+
+        if !name.contains(['*', '?']) {
+            candidates.iter_mut().for_each(|c| c.push(name.as_ref()));
+            continue;
+        }
+
+        let mut matches = Vec::new();
+        for candidate in &candidates {
+            let directory = if candidate.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                candidate.as_path()
+            };
+            let Ok(entries) = std::fs::read_dir(directory) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                if wildcard_match(&name, &entry.file_name().to_string_lossy()) {
+                    matches.push(candidate.join(entry.file_name()));
+                }
+            }
+        }
+        matches.sort();
+        candidates = matches;
+    }
+
+    candidates.into_iter().find(|path| path.exists())
+}
+
+/// Tells if `name` matches `pattern`, where `*` matches any sequence and `?` any character
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    // Position of the last `*` in the pattern, and of the name when it was found
+    let mut star = None;
+    let (mut p, mut n) = (0, 0);
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("BAT*", "BAT0"));
+        assert!(wildcard_match("BAT?", "BAT1"));
+        assert!(wildcard_match("*", "intel_backlight"));
+        assert!(wildcard_match("*_backlight", "intel_backlight"));
+        assert!(wildcard_match("a*b*c", "aXbYbZc"));
+        assert!(!wildcard_match("BAT?", "BAT10"));
+        assert!(!wildcard_match("AC*", "BAT0"));
+    }
+
+    #[test]
+    fn test_read() -> Result<(), Box<dyn Error>> {
+        let root =
+            std::env::temp_dir().join(format!("mqtt-system-monitor-file-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("backlight/intel_backlight"))?;
+        std::fs::create_dir_all(root.join("power_supply/BAT1"))?;
+        std::fs::write(root.join("backlight/intel_backlight/brightness"), "4800\n")?;
+        std::fs::write(root.join("power_supply/BAT1/status"), "Charging\n")?;
+
+        let brightness = read(&format!("{}/backlight/*/brightness", root.display()), 0.01);
+        let status = read(&format!("{}/power_supply/BAT?/status", root.display()), 1.0);
+        let missing = read(&format!("{}/power_supply/AC*/online", root.display()), 1.0);
+        std::fs::remove_dir_all(&root)?;
+
+        assert_eq!(brightness?, Value::from(48.0));
+        assert_eq!(status?, Value::from("Charging"));
+        assert!(missing.is_err());
+
+        Ok(())
+    }
+}
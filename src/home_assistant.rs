@@ -1,5 +1,8 @@
+use crate::systemd::ACTIVE_STATES;
+use crate::zfs::HEALTHS as ZFS_HEALTHS;
 use convert_case::{Case, Casing};
 use serde::Serialize;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt;
 use strum_macros::EnumIter;
@@ -13,17 +16,196 @@ pub enum Sensor {
     /// Sends the CPU usage in %
     CpuUsage,
 
+    /// Sends the usage of a logical core in %
+    CpuCoreUsage(usize),
+
+    /// Sends the frequency of a logical core in MHz
+    CpuCoreFrequency(usize),
+
     /// Sends a temperature in °C
     Temperature(String, String),
 
+    /// Tells if a temperature crossed its warning threshold
+    TemperatureWarning(String, String),
+
+    /// Tells if a temperature crossed its critical threshold
+    TemperatureCritical(String, String),
+
+    /// Sends a fan speed in RPM
+    Fan(String, String),
+
+    /// Sends a voltage in V
+    Voltage(String, String),
+
     /// Sends the memory usage in %
     MemoryUsage,
 
+    /// Sends the swap usage in %
+    SwapUsage,
+
+    /// Sends the available memory in bytes
+    MemoryAvailable,
+
+    /// Sends the memory used by the page cache in bytes
+    MemoryCached,
+
+    /// Sends the memory used by the buffers in bytes
+    MemoryBuffers,
+
     /// Sends the download network rate in KiB/s
     NetRx(String),
 
     /// Sends the upload network rate in KiB/s
     NetTx(String),
+
+    /// Sends the received packets per second
+    NetRxPackets(String),
+
+    /// Sends the transmitted packets per second
+    NetTxPackets(String),
+
+    /// Sends the reception errors per second
+    NetRxErrors(String),
+
+    /// Sends the transmission errors per second
+    NetTxErrors(String),
+
+    /// Sends the dropped received packets per second
+    NetRxDrops(String),
+
+    /// Sends the dropped transmitted packets per second
+    NetTxDrops(String),
+
+    /// Sends the total amount of received data in bytes
+    NetRxTotal(String),
+
+    /// Sends the total amount of transmitted data in bytes
+    NetTxTotal(String),
+
+    /// Sends the link quality of a wireless interface
+    WifiLinkQuality(String),
+
+    /// Sends the signal level of a wireless interface in dBm
+    WifiSignal(String),
+
+    /// Sends the noise level of a wireless interface in dBm
+    WifiNoise(String),
+
+    /// Tells if the link of a network interface is up
+    NetLink(String),
+
+    /// Sends the IPv4 addresses of a network interface
+    NetIpv4(String),
+
+    /// Sends the IPv6 addresses of a network interface
+    NetIpv6(String),
+
+    /// Sends the MAC address of a network interface
+    NetMac(String),
+
+    /// Sends the disk usage of a mount point in %
+    DiskUsage(String),
+
+    /// Sends the used space of a mount point in bytes
+    DiskUsed(String),
+
+    /// Sends the free space of a mount point in bytes
+    DiskFree(String),
+
+    /// Sends the inode usage of a mount point in %
+    DiskInodeUsage(String),
+
+    /// Sends the read rate of a block device in KiB/s
+    DiskRead(String),
+
+    /// Sends the write rate of a block device in KiB/s
+    DiskWrite(String),
+
+    /// Sends the read operations of a block device per second
+    DiskReadOps(String),
+
+    /// Sends the write operations of a block device per second
+    DiskWriteOps(String),
+
+    /// Tells if a watched process is running
+    ProcessRunning(String),
+
+    /// Sends the CPU usage of a watched process in %
+    ProcessCpuUsage(String),
+
+    /// Sends the resident memory of a watched process in bytes
+    ProcessMemory(String),
+
+    /// Sends the `ActiveState` of a systemd unit
+    UnitActiveState(String),
+
+    /// Sends the `SubState` of a systemd unit
+    UnitSubState(String),
+
+    /// Tells if a systemd unit failed
+    UnitFailed(String),
+
+    /// Tells if a software RAID array is inactive or degraded
+    RaidProblem(String),
+
+    /// Sends the resync progress of a software RAID array in %
+    RaidSyncProgress(String),
+
+    /// Tells if a ZFS pool is not healthy
+    ZfsProblem(String),
+
+    /// Sends the health of a ZFS pool
+    ZfsHealth(String),
+
+    /// Sends the used capacity of a ZFS pool in %
+    ZfsCapacity(String),
+
+    /// Sends the scrub or resilver progress of a ZFS pool in %
+    ZfsScanProgress(String),
+
+    /// Sends the value of a custom command
+    Command {
+        name: String,
+        unit: Option<String>,
+        device_class: Option<String>,
+    },
+
+    /// Sends the value read from a file
+    File {
+        name: String,
+        unit: Option<String>,
+        device_class: Option<String>,
+    },
+
+    /// Sends a Pressure Stall Information value in %, with the resource and the field
+    Pressure(String, String),
+
+    /// Sends the battery level in %
+    BatteryLevel,
+
+    /// Tells if the battery is charging
+    BatteryCharging,
+
+    /// Tells if the AC adapter is plugged
+    AcConnected,
+
+    /// Sends the load average over the last minute
+    LoadAverage1,
+
+    /// Sends the load average over the last 5 minutes
+    LoadAverage5,
+
+    /// Sends the load average over the last 15 minutes
+    LoadAverage15,
+
+    /// Sends the boot time of the machine
+    Uptime,
+
+    /// Tells if a reboot is required to apply the updates
+    RebootRequired,
+
+    /// Sends the number of pending updates
+    PendingUpdates,
 }
 
 impl Sensor {
@@ -32,14 +214,121 @@ impl Sensor {
         match self {
             Sensor::Available => "available".to_string(),
             Sensor::CpuUsage => "cpu_usage".to_string(),
+            Sensor::CpuCoreUsage(core) => format!("cpu{core}_usage"),
+            Sensor::CpuCoreFrequency(core) => format!("cpu{core}_frequency"),
             Sensor::Temperature(id, _) => format!("{id}_temp"),
+            Sensor::TemperatureWarning(id, _) => format!("{id}_temp_warning"),
+            Sensor::TemperatureCritical(id, _) => format!("{id}_temp_critical"),
+            Sensor::Fan(id, _) => format!("{id}_speed"),
+            Sensor::Voltage(id, _) => format!("{id}_voltage"),
             Sensor::MemoryUsage => "memory_usage".to_string(),
+            Sensor::SwapUsage => "swap_usage".to_string(),
+            Sensor::MemoryAvailable => "memory_available".to_string(),
+            Sensor::MemoryCached => "memory_cached".to_string(),
+            Sensor::MemoryBuffers => "memory_buffers".to_string(),
             Sensor::NetRx(interface) => format!("{interface}_net_rx"),
             Sensor::NetTx(interface) => format!("{interface}_net_tx"),
+            Sensor::NetRxPackets(interface) => format!("{interface}_net_rx_packets"),
+            Sensor::NetTxPackets(interface) => format!("{interface}_net_tx_packets"),
+            Sensor::NetRxErrors(interface) => format!("{interface}_net_rx_errors"),
+            Sensor::NetTxErrors(interface) => format!("{interface}_net_tx_errors"),
+            Sensor::NetRxDrops(interface) => format!("{interface}_net_rx_drops"),
+            Sensor::NetTxDrops(interface) => format!("{interface}_net_tx_drops"),
+            Sensor::NetRxTotal(interface) => format!("{interface}_net_rx_total"),
+            Sensor::NetTxTotal(interface) => format!("{interface}_net_tx_total"),
+            Sensor::WifiLinkQuality(interface) => format!("{interface}_wifi_link_quality"),
+            Sensor::WifiSignal(interface) => format!("{interface}_wifi_signal"),
+            Sensor::WifiNoise(interface) => format!("{interface}_wifi_noise"),
+            Sensor::NetLink(interface) => format!("{interface}_net_link"),
+            Sensor::NetIpv4(interface) => format!("{interface}_net_ipv4"),
+            Sensor::NetIpv6(interface) => format!("{interface}_net_ipv6"),
+            Sensor::NetMac(interface) => format!("{interface}_net_mac"),
+            Sensor::DiskUsage(mount) => format!("{}_disk_usage", mount_key(mount)),
+            Sensor::DiskUsed(mount) => format!("{}_disk_used", mount_key(mount)),
+            Sensor::DiskFree(mount) => format!("{}_disk_free", mount_key(mount)),
+            Sensor::DiskInodeUsage(mount) => format!("{}_disk_inode_usage", mount_key(mount)),
+            Sensor::DiskRead(device) => format!("{}_disk_read", key(device)),
+            Sensor::DiskWrite(device) => format!("{}_disk_write", key(device)),
+            Sensor::DiskReadOps(device) => format!("{}_disk_read_ops", key(device)),
+            Sensor::DiskWriteOps(device) => format!("{}_disk_write_ops", key(device)),
+            Sensor::ProcessRunning(name) => format!("{}_process_running", key(name)),
+            Sensor::ProcessCpuUsage(name) => format!("{}_process_cpu_usage", key(name)),
+            Sensor::ProcessMemory(name) => format!("{}_process_memory", key(name)),
+            Sensor::UnitActiveState(unit) => format!("{}_active_state", key(unit)),
+            Sensor::UnitSubState(unit) => format!("{}_sub_state", key(unit)),
+            Sensor::UnitFailed(unit) => format!("{}_failed", key(unit)),
+            Sensor::RaidProblem(array) => format!("{}_raid_problem", key(array)),
+            Sensor::RaidSyncProgress(array) => format!("{}_raid_sync_progress", key(array)),
+            Sensor::ZfsProblem(pool) => format!("{}_zfs_problem", key(pool)),
+            Sensor::ZfsHealth(pool) => format!("{}_zfs_health", key(pool)),
+            Sensor::ZfsCapacity(pool) => format!("{}_zfs_capacity", key(pool)),
+            Sensor::ZfsScanProgress(pool) => format!("{}_zfs_scan_progress", key(pool)),
+            Sensor::Command { name, .. } => format!("{}_command", key(name)),
+            Sensor::File { name, .. } => format!("{}_file", key(name)),
+            Sensor::Pressure(resource, field) => format!("pressure_{resource}_{field}"),
+            Sensor::BatteryLevel => "battery_level".to_string(),
+            Sensor::BatteryCharging => "battery_charging".to_string(),
+            Sensor::AcConnected => "ac_connected".to_string(),
+            Sensor::LoadAverage1 => "load_average_1".to_string(),
+            Sensor::LoadAverage5 => "load_average_5".to_string(),
+            Sensor::LoadAverage15 => "load_average_15".to_string(),
+            Sensor::Uptime => "uptime".to_string(),
+            Sensor::RebootRequired => "reboot_required".to_string(),
+            Sensor::PendingUpdates => "pending_updates".to_string(),
         }
     }
 }
 
+/// Payload of the availability topic when the device is available
+pub const PAYLOAD_AVAILABLE: &str = "ON";
+
+/// Payload of the availability topic when the device is not available
+pub const PAYLOAD_NOT_AVAILABLE: &str = "OFF";
+
+/// Returns the availability topic of an entity
+fn availability_topic(entity: &str) -> String {
+    format!("mqtt-system-monitor/{entity}/availability")
+}
+
+/// Converts a name to a key usable in the status and the unique IDs
+///
+/// ## Example
+///
+/// ```
+/// use mqtt_system_monitor::home_assistant::key;
+///
+/// assert_eq!(key("dm-0"), "dm_0");
+/// assert_eq!(key("/var/lib"), "var_lib");
+/// ```
+pub fn key(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>()
+        .trim_matches('_')
+        .to_string()
+}
+
+/// Converts a mount point to a key usable in the status and the unique IDs
+///
+/// The root filesystem is `root`, the other mount points are prefixed with `mnt_` so that they
+/// cannot collide with it.
+///
+/// ## Example
+///
+/// ```
+/// use mqtt_system_monitor::home_assistant::mount_key;
+///
+/// assert_eq!(mount_key("/"), "root");
+/// assert_eq!(mount_key("/root"), "mnt_root");
+/// assert_eq!(mount_key("/var/lib"), "mnt_var_lib");
+/// ```
+pub fn mount_key(mount: &str) -> String {
+    match key(mount) {
+        key if key.is_empty() => "root".to_string(),
+        key => format!("mnt_{key}"),
+    }
+}
+
 /// Registration descriptor sent to Home Assistant
 ///
 /// This describes the device and its components (the sensors that are configured)
@@ -56,6 +345,22 @@ pub struct RegistrationDescriptor {
 
     /// Topic that is sent to MQTT when the state changes
     state_topic: String,
+
+    /// Topic that tells if the device is available, shared by all the components
+    availability: [Availability; 1],
+}
+
+/// Availability topic sent to Home Assistant
+#[derive(Serialize, Debug)]
+pub struct Availability {
+    /// Topic that tells if the device is available
+    topic: String,
+
+    /// Payload of the availability topic when the device is available
+    payload_available: &'static str,
+
+    /// Payload of the availability topic when the device is not available
+    payload_not_available: &'static str,
 }
 
 /// Device sent to Home Assistant
@@ -93,7 +398,7 @@ pub struct DeviceComponent {
     /// Device class helps Home Assistant to know how to interpret the reported values.
     ///
     /// See <https://www.home-assistant.io/integrations/sensor#device-class> for possible values here
-    device_class: Option<&'static str>,
+    device_class: Option<Cow<'static, str>>,
 
     /// An icon for certain sensors that are too generic (for example when `device_class` is `None`)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -105,7 +410,23 @@ pub struct DeviceComponent {
 
     /// Unit used in the report
     #[serde(skip_serializing_if = "Option::is_none")]
-    unit_of_measurement: Option<&'static str>,
+    unit_of_measurement: Option<Cow<'static, str>>,
+
+    /// Possible values when `device_class` is `enum`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<&'static [&'static str]>,
+
+    /// Category of the entity, `diagnostic` for the values that are not the primary data
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entity_category: Option<&'static str>,
+
+    /// Topic of the values, if it is not the state topic of the device
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state_topic: Option<String>,
+
+    /// Availability topics, if they are not the availability topic of the device
+    #[serde(skip_serializing_if = "Option::is_none")]
+    availability: Option<&'static [Availability]>,
 
     /// Unique ID for the component. This is constructed from the entity and the sensor type
     unique_id: String,
@@ -150,6 +471,11 @@ impl RegistrationDescriptor {
             },
             components: Default::default(),
             state_topic: format!("mqtt-system-monitor/{entity}/state"),
+            availability: [Availability {
+                topic: availability_topic(&entity),
+                payload_available: PAYLOAD_AVAILABLE,
+                payload_not_available: PAYLOAD_NOT_AVAILABLE,
+            }],
         }
     }
 
@@ -188,11 +514,23 @@ impl RegistrationDescriptor {
         format!("{prefix}/device/{}/config", self.device.identifiers)
     }
 
+    /// Topic where Home Assistant sends its birth message, `online`, when it starts
+    pub fn birth_topic(prefix: &str) -> String {
+        format!("{prefix}/status")
+    }
+
     /// Discovery topic for this sensor if individual updates are sent
     pub fn state_topic(&self) -> &str {
         &self.state_topic
     }
 
+    /// Topic that tells if the device is available
+    ///
+    /// It is retained, and set to `OFF` by the broker if the connection is lost
+    pub fn availability_topic(&self) -> &str {
+        &self.availability[0].topic
+    }
+
     /// Returns the registration descriptor
     pub fn components(&self) -> &HashMap<String, DeviceComponent> {
         &self.components
@@ -215,24 +553,130 @@ impl DeviceComponent {
         match sensor {
             Sensor::Available => Self::available(entity),
             Sensor::CpuUsage => Self::cpu_usage(entity),
+            Sensor::CpuCoreUsage(core) => Self::cpu_core_usage(entity, core),
+            Sensor::CpuCoreFrequency(core) => Self::cpu_core_frequency(entity, core),
             Sensor::MemoryUsage => Self::memory_usage(entity),
+            Sensor::SwapUsage => Self::swap_usage(entity),
+            Sensor::MemoryAvailable => {
+                Self::memory_size(entity, "memory_available", "Memory available")
+            }
+            Sensor::MemoryCached => Self::memory_size(entity, "memory_cached", "Memory cached"),
+            Sensor::MemoryBuffers => Self::memory_size(entity, "memory_buffers", "Memory buffers"),
             Sensor::NetRx(interface) => Self::net_rx(entity, &interface),
             Sensor::NetTx(interface) => Self::net_tx(entity, &interface),
+            Sensor::NetRxPackets(interface) => {
+                Self::net_counter(entity, &interface, "rx_packets", "RX packets", "packets/s")
+            }
+            Sensor::NetTxPackets(interface) => {
+                Self::net_counter(entity, &interface, "tx_packets", "TX packets", "packets/s")
+            }
+            Sensor::NetRxErrors(interface) => {
+                Self::net_counter(entity, &interface, "rx_errors", "RX errors", "errors/s")
+            }
+            Sensor::NetTxErrors(interface) => {
+                Self::net_counter(entity, &interface, "tx_errors", "TX errors", "errors/s")
+            }
+            Sensor::NetRxDrops(interface) => {
+                Self::net_counter(entity, &interface, "rx_drops", "RX drops", "packets/s")
+            }
+            Sensor::NetTxDrops(interface) => {
+                Self::net_counter(entity, &interface, "tx_drops", "TX drops", "packets/s")
+            }
+            Sensor::NetRxTotal(interface) => Self::net_total(entity, &interface, "rx", "RX"),
+            Sensor::NetTxTotal(interface) => Self::net_total(entity, &interface, "tx", "TX"),
+            Sensor::WifiLinkQuality(interface) => Self::wifi_link_quality(entity, &interface),
+            Sensor::WifiSignal(interface) => {
+                Self::wifi_level(entity, &interface, "wifi_signal", "Wi-Fi signal")
+            }
+            Sensor::WifiNoise(interface) => {
+                Self::wifi_level(entity, &interface, "wifi_noise", "Wi-Fi noise")
+            }
+            Sensor::NetLink(interface) => Self::net_link(entity, &interface),
+            Sensor::NetIpv4(interface) => Self::net_diagnostic(
+                entity,
+                &interface,
+                "ipv4",
+                "IPv4 addresses",
+                "mdi:ip-network",
+            ),
+            Sensor::NetIpv6(interface) => Self::net_diagnostic(
+                entity,
+                &interface,
+                "ipv6",
+                "IPv6 addresses",
+                "mdi:ip-network",
+            ),
+            Sensor::NetMac(interface) => {
+                Self::net_diagnostic(entity, &interface, "mac", "MAC address", "mdi:network")
+            }
             Sensor::Temperature(id, label) => Self::temperature(entity, &id, &label),
+            Sensor::TemperatureWarning(id, label) => {
+                Self::temperature_problem(entity, &id, &label, "warning")
+            }
+            Sensor::TemperatureCritical(id, label) => {
+                Self::temperature_problem(entity, &id, &label, "critical")
+            }
+            Sensor::Fan(id, label) => Self::fan(entity, &id, &label),
+            Sensor::Voltage(id, label) => Self::voltage(entity, &id, &label),
+            Sensor::DiskUsage(mount) => Self::disk_usage(entity, &mount),
+            Sensor::DiskUsed(mount) => Self::disk_used(entity, &mount),
+            Sensor::DiskFree(mount) => Self::disk_free(entity, &mount),
+            Sensor::DiskInodeUsage(mount) => Self::disk_inode_usage(entity, &mount),
+            Sensor::DiskRead(device) => Self::disk_read(entity, &device),
+            Sensor::DiskWrite(device) => Self::disk_write(entity, &device),
+            Sensor::DiskReadOps(device) => Self::disk_read_ops(entity, &device),
+            Sensor::DiskWriteOps(device) => Self::disk_write_ops(entity, &device),
+            Sensor::ProcessRunning(name) => Self::process_running(entity, &name),
+            Sensor::ProcessCpuUsage(name) => Self::process_cpu_usage(entity, &name),
+            Sensor::ProcessMemory(name) => Self::process_memory(entity, &name),
+            Sensor::UnitActiveState(unit) => Self::unit_active_state(entity, &unit),
+            Sensor::UnitSubState(unit) => Self::unit_sub_state(entity, &unit),
+            Sensor::UnitFailed(unit) => Self::unit_failed(entity, &unit),
+            Sensor::RaidProblem(array) => Self::raid_problem(entity, &array),
+            Sensor::RaidSyncProgress(array) => Self::raid_sync_progress(entity, &array),
+            Sensor::ZfsProblem(pool) => Self::zfs_problem(entity, &pool),
+            Sensor::ZfsHealth(pool) => Self::zfs_health(entity, &pool),
+            Sensor::ZfsCapacity(pool) => Self::zfs_capacity(entity, &pool),
+            Sensor::ZfsScanProgress(pool) => Self::zfs_scan_progress(entity, &pool),
+            Sensor::Command {
+                name,
+                unit,
+                device_class,
+            } => Self::command(entity, &name, unit, device_class),
+            Sensor::File {
+                name,
+                unit,
+                device_class,
+            } => Self::file(entity, &name, unit, device_class),
+            Sensor::Pressure(resource, field) => Self::pressure(entity, &resource, &field),
+            Sensor::BatteryLevel => Self::battery_level(entity),
+            Sensor::BatteryCharging => Self::battery_charging(entity),
+            Sensor::AcConnected => Self::ac_connected(entity),
+            Sensor::LoadAverage1 => Self::load_average(entity, 1),
+            Sensor::LoadAverage5 => Self::load_average(entity, 5),
+            Sensor::LoadAverage15 => Self::load_average(entity, 15),
+            Sensor::Uptime => Self::uptime(entity),
+            Sensor::RebootRequired => Self::reboot_required(entity),
+            Sensor::PendingUpdates => Self::pending_updates(entity),
         }
     }
 
-    /// Manually creates a CPU temperature sensor
+    /// Manually creates an availability sensor, reading the availability topic
     fn available(entity: &str) -> DeviceComponent {
         DeviceComponent {
             name: None,
             platform: "binary_sensor",
-            device_class: Some("connectivity"),
+            device_class: Some("connectivity".into()),
             icon: None,
             state_class: None,
             unit_of_measurement: None,
+            options: None,
+            entity_category: None,
+            state_topic: Some(availability_topic(entity)),
+            // Always available, so that it reports OFF when the device is not
+            availability: Some(&[]),
             unique_id: format!("{entity}_available"),
-            value_template: "{{ value_json.available }}".to_string(),
+            value_template: "{{ value }}".to_string(),
             expire_after: None,
         }
     }
@@ -245,13 +689,59 @@ impl DeviceComponent {
             device_class: None,
             state_class: Some("measurement"),
             icon: Some("mdi:cpu-64-bit"),
-            unit_of_measurement: Some("%"),
+            unit_of_measurement: Some("%".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
             unique_id: format!("{entity}_cpu_usage"),
             value_template: "{{ value_json.cpu_usage }}".to_string(),
             expire_after: Some(60),
         }
     }
 
+    /// Manually creates a logical core usage sensor
+    fn cpu_core_usage(entity: &str, core: usize) -> DeviceComponent {
+        DeviceComponent {
+            name: Some(format!("CPU{core} usage")),
+            platform: "sensor",
+            device_class: None,
+            state_class: Some("measurement"),
+            icon: Some("mdi:cpu-64-bit"),
+            unit_of_measurement: Some("%".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_cpu{core}_usage"),
+            value_template: format!(
+                "{{{{ value_json.cpus[{core}].usage if value_json.cpus|length > {core} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a logical core frequency sensor
+    fn cpu_core_frequency(entity: &str, core: usize) -> DeviceComponent {
+        DeviceComponent {
+            name: Some(format!("CPU{core} frequency")),
+            platform: "sensor",
+            device_class: Some("frequency".into()),
+            state_class: Some("measurement"),
+            icon: None,
+            unit_of_measurement: Some("MHz".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_cpu{core}_frequency"),
+            value_template: format!(
+                "{{{{ value_json.cpus[{core}].frequency if value_json.cpus|length > {core} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
     /// Manually creates a Memory usage sensor
     fn memory_usage(entity: &str) -> DeviceComponent {
         DeviceComponent {
@@ -260,25 +750,134 @@ impl DeviceComponent {
             device_class: None,
             state_class: Some("measurement"),
             icon: Some("mdi:memory"),
-            unit_of_measurement: Some("%"),
+            unit_of_measurement: Some("%".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
             unique_id: format!("{entity}_memory_usage"),
             value_template: "{{ value_json.memory_usage }}".to_string(),
             expire_after: Some(60),
         }
     }
 
+    /// Manually creates a Swap usage sensor
+    fn swap_usage(entity: &str) -> DeviceComponent {
+        DeviceComponent {
+            name: Some("Swap usage".to_string()),
+            platform: "sensor",
+            device_class: None,
+            state_class: Some("measurement"),
+            icon: Some("mdi:memory"),
+            unit_of_measurement: Some("%".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_swap_usage"),
+            value_template: "{{ value_json.swap_usage }}".to_string(),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a memory size sensor, reading the `field` value in bytes
+    fn memory_size(entity: &str, field: &str, name: &str) -> DeviceComponent {
+        DeviceComponent {
+            name: Some(name.to_string()),
+            platform: "sensor",
+            device_class: Some("data_size".into()),
+            state_class: Some("measurement"),
+            icon: Some("mdi:memory"),
+            unit_of_measurement: Some("B".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{field}"),
+            value_template: format!("{{{{ value_json.{field} }}}}"),
+            expire_after: Some(60),
+        }
+    }
+
     /// Manually creates a temperature sensor
     fn temperature(entity: &str, id: &str, label: &str) -> DeviceComponent {
         DeviceComponent {
             name: Some(label.to_string()),
             platform: "sensor",
-            device_class: Some("temperature"),
+            device_class: Some("temperature".into()),
             icon: None,
             state_class: Some("measurement"),
-            unit_of_measurement: Some("°C"),
+            unit_of_measurement: Some("°C".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
             unique_id: format!("{entity}_{id}_temp"),
             value_template: format!(
-                "{{{{ value_json.temperature.{id} if value_json.temperature and value_json.temperature.{id} else None }}}}"
+                "{{{{ value_json.temperature.{id} if value_json.temperature.{id} is defined else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a temperature problem sensor for the `warning` or `critical` threshold
+    fn temperature_problem(entity: &str, id: &str, label: &str, level: &str) -> DeviceComponent {
+        DeviceComponent {
+            name: Some(format!("{label} {level}")),
+            platform: "binary_sensor",
+            device_class: Some("problem".into()),
+            icon: None,
+            state_class: None,
+            unit_of_measurement: None,
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{id}_temp_{level}"),
+            value_template: format!(
+                "{{{{ value_json.temperature_{level}.{id} if value_json.temperature_{level}.{id} is defined else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a fan speed sensor
+    fn fan(entity: &str, id: &str, label: &str) -> DeviceComponent {
+        DeviceComponent {
+            name: Some(label.to_string()),
+            platform: "sensor",
+            device_class: None,
+            icon: Some("mdi:fan"),
+            state_class: Some("measurement"),
+            unit_of_measurement: Some("RPM".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{id}_speed"),
+            value_template: format!(
+                "{{{{ value_json.fan.{id} if value_json.fan.{id} is defined else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a voltage sensor
+    fn voltage(entity: &str, id: &str, label: &str) -> DeviceComponent {
+        DeviceComponent {
+            name: Some(label.to_string()),
+            platform: "sensor",
+            device_class: Some("voltage".into()),
+            icon: None,
+            state_class: Some("measurement"),
+            unit_of_measurement: Some("V".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{id}_voltage"),
+            value_template: format!(
+                "{{{{ value_json.voltage.{id} if value_json.voltage.{id} is defined else None }}}}"
             ),
             expire_after: Some(60),
         }
@@ -289,10 +888,14 @@ impl DeviceComponent {
         DeviceComponent {
             name: Some(format!("{interface} Network RX rate")),
             platform: "sensor",
-            device_class: Some("data_rate"),
+            device_class: Some("data_rate".into()),
             state_class: Some("measurement"),
             icon: None,
-            unit_of_measurement: Some("KiB/s"),
+            unit_of_measurement: Some("KiB/s".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
             unique_id: format!("{entity}_{interface}_net_rx"),
             value_template: format!(
                 "{{{{ value_json.network.{interface}.rx if value_json.network.{interface} else None }}}}"
@@ -306,10 +909,14 @@ impl DeviceComponent {
         DeviceComponent {
             name: Some(format!("{interface} Network TX rate")),
             platform: "sensor",
-            device_class: Some("data_rate"),
+            device_class: Some("data_rate".into()),
             state_class: Some("measurement"),
             icon: None,
-            unit_of_measurement: Some("KiB/s"),
+            unit_of_measurement: Some("KiB/s".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
             unique_id: format!("{entity}_{interface}_net_tx"),
             value_template: format!(
                 "{{{{ value_json.network.{interface}.tx if value_json.network.{interface} else None }}}}"
@@ -318,49 +925,918 @@ impl DeviceComponent {
         }
     }
 
-    pub fn value_template(&self) -> &str {
-        &self.value_template
+    /// Manually creates a Network packet counter sensor, reading the `field` of the interface
+    fn net_counter(
+        entity: &str,
+        interface: &str,
+        field: &str,
+        name: &str,
+        unit: &'static str,
+    ) -> DeviceComponent {
+        DeviceComponent {
+            name: Some(format!("{interface} Network {name}")),
+            platform: "sensor",
+            device_class: None,
+            state_class: Some("measurement"),
+            icon: Some("mdi:network"),
+            unit_of_measurement: Some(unit.into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{interface}_net_{field}"),
+            value_template: format!(
+                "{{{{ value_json.network.{interface}.{field} if value_json.network.{interface} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::DeviceComponent;
-    use crate::home_assistant::{RegistrationDescriptor, Sensor};
-    use strum::IntoEnumIterator;
-
-    #[test]
-    fn test_registration() {
-        let name = "Test Entity";
-        let entity = "test_entity";
-        let mut descriptor = RegistrationDescriptor::new(name);
 
-        Sensor::iter().for_each(|sensor| descriptor.add_component(sensor));
-
-        assert_eq!(descriptor.device.name, name);
-        assert_eq!(descriptor.device.identifiers, entity);
-
-        assert_eq!(
-            descriptor.state_topic,
-            "mqtt-system-monitor/test_entity/state"
-        );
+    /// Manually creates a Network total sensor, reading the `{direction}_total` field
+    ///
+    /// The counters are reset when the system reboots, which Home Assistant handles as a new
+    /// cycle of a `total_increasing` sensor
+    fn net_total(entity: &str, interface: &str, direction: &str, name: &str) -> DeviceComponent {
+        DeviceComponent {
+            name: Some(format!("{interface} Network {name} total")),
+            platform: "sensor",
+            device_class: Some("data_size".into()),
+            state_class: Some("total_increasing"),
+            icon: None,
+            unit_of_measurement: Some("B".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{interface}_net_{direction}_total"),
+            value_template: format!(
+                "{{{{ value_json.network.{interface}.{direction}_total if value_json.network.{interface} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
 
-        for component in &descriptor.components {
-            assert_eq!(component.1.unique_id, format!("{entity}_{}", component.0));
-            if component.1.name.is_some() {
-                assert_eq!(component.1.state_class, Some("measurement"));
-            }
+    /// Manually creates a Wi-Fi link quality sensor
+    fn wifi_link_quality(entity: &str, interface: &str) -> DeviceComponent {
+        DeviceComponent {
+            name: Some(format!("{interface} Wi-Fi link quality")),
+            platform: "sensor",
+            device_class: None,
+            state_class: Some("measurement"),
+            icon: Some("mdi:wifi"),
+            unit_of_measurement: None,
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{interface}_wifi_link_quality"),
+            value_template: format!(
+                "{{{{ value_json.network.{interface}.wifi_link_quality if value_json.network.{interface} else None }}}}"
+            ),
+            expire_after: Some(60),
         }
+    }
 
-        let cpu_usage = descriptor
-            .components
-            .get("cpu_usage")
-            .expect("component cpu_usage not found");
+    /// Manually creates a Wi-Fi level sensor in dBm, reading the `field` of the interface
+    fn wifi_level(entity: &str, interface: &str, field: &str, name: &str) -> DeviceComponent {
+        DeviceComponent {
+            name: Some(format!("{interface} {name}")),
+            platform: "sensor",
+            device_class: Some("signal_strength".into()),
+            state_class: Some("measurement"),
+            icon: None,
+            unit_of_measurement: Some("dBm".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{interface}_{field}"),
+            value_template: format!(
+                "{{{{ value_json.network.{interface}.{field} if value_json.network.{interface} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
 
-        assert_eq!(cpu_usage.device_class, None);
+    /// Manually creates a Network link sensor
+    ///
+    /// The link is down when the interface disappears
+    fn net_link(entity: &str, interface: &str) -> DeviceComponent {
+        DeviceComponent {
+            name: Some(format!("{interface} Network link")),
+            platform: "binary_sensor",
+            device_class: Some("connectivity".into()),
+            state_class: None,
+            icon: None,
+            unit_of_measurement: None,
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{interface}_net_link"),
+            value_template: format!(
+                "{{{{ value_json.network.{interface}.link if value_json.network.{interface} else 'OFF' }}}}"
+            ),
+            expire_after: Some(60),
+        }
     }
 
-    /// Test that all sensors can be created
+    /// Manually creates a Network diagnostic text sensor, reading the `field` of the interface
+    fn net_diagnostic(
+        entity: &str,
+        interface: &str,
+        field: &str,
+        name: &str,
+        icon: &'static str,
+    ) -> DeviceComponent {
+        DeviceComponent {
+            name: Some(format!("{interface} {name}")),
+            platform: "sensor",
+            device_class: None,
+            state_class: None,
+            icon: Some(icon),
+            unit_of_measurement: None,
+            options: None,
+            entity_category: Some("diagnostic"),
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{interface}_net_{field}"),
+            value_template: format!(
+                "{{{{ value_json.network.{interface}.{field} if value_json.network.{interface} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a disk usage sensor
+    fn disk_usage(entity: &str, mount: &str) -> DeviceComponent {
+        let key = mount_key(mount);
+        DeviceComponent {
+            name: Some(format!("{mount} Disk usage")),
+            platform: "sensor",
+            device_class: None,
+            state_class: Some("measurement"),
+            icon: Some("mdi:harddisk"),
+            unit_of_measurement: Some("%".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_disk_usage"),
+            value_template: format!(
+                "{{{{ value_json.disk.{key}.usage if value_json.disk.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a disk used space sensor
+    fn disk_used(entity: &str, mount: &str) -> DeviceComponent {
+        let key = mount_key(mount);
+        DeviceComponent {
+            name: Some(format!("{mount} Disk used")),
+            platform: "sensor",
+            device_class: Some("data_size".into()),
+            state_class: Some("measurement"),
+            icon: None,
+            unit_of_measurement: Some("B".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_disk_used"),
+            value_template: format!(
+                "{{{{ value_json.disk.{key}.used if value_json.disk.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a disk free space sensor
+    fn disk_free(entity: &str, mount: &str) -> DeviceComponent {
+        let key = mount_key(mount);
+        DeviceComponent {
+            name: Some(format!("{mount} Disk free")),
+            platform: "sensor",
+            device_class: Some("data_size".into()),
+            state_class: Some("measurement"),
+            icon: None,
+            unit_of_measurement: Some("B".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_disk_free"),
+            value_template: format!(
+                "{{{{ value_json.disk.{key}.free if value_json.disk.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a disk inode usage sensor
+    fn disk_inode_usage(entity: &str, mount: &str) -> DeviceComponent {
+        let key = mount_key(mount);
+        DeviceComponent {
+            name: Some(format!("{mount} Inode usage")),
+            platform: "sensor",
+            device_class: None,
+            state_class: Some("measurement"),
+            icon: Some("mdi:file-multiple"),
+            unit_of_measurement: Some("%".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_disk_inode_usage"),
+            value_template: format!(
+                "{{{{ value_json.disk.{key}.inode_usage if value_json.disk.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a disk read rate sensor
+    fn disk_read(entity: &str, device: &str) -> DeviceComponent {
+        let key = key(device);
+        DeviceComponent {
+            name: Some(format!("{device} Disk read rate")),
+            platform: "sensor",
+            device_class: Some("data_rate".into()),
+            state_class: Some("measurement"),
+            icon: None,
+            unit_of_measurement: Some("KiB/s".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_disk_read"),
+            value_template: format!(
+                "{{{{ value_json.disk_io.{key}.read if value_json.disk_io.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a disk write rate sensor
+    fn disk_write(entity: &str, device: &str) -> DeviceComponent {
+        let key = key(device);
+        DeviceComponent {
+            name: Some(format!("{device} Disk write rate")),
+            platform: "sensor",
+            device_class: Some("data_rate".into()),
+            state_class: Some("measurement"),
+            icon: None,
+            unit_of_measurement: Some("KiB/s".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_disk_write"),
+            value_template: format!(
+                "{{{{ value_json.disk_io.{key}.write if value_json.disk_io.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a disk read operations sensor
+    fn disk_read_ops(entity: &str, device: &str) -> DeviceComponent {
+        let key = key(device);
+        DeviceComponent {
+            name: Some(format!("{device} Disk read operations")),
+            platform: "sensor",
+            device_class: None,
+            state_class: Some("measurement"),
+            icon: Some("mdi:harddisk"),
+            unit_of_measurement: Some("ops/s".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_disk_read_ops"),
+            value_template: format!(
+                "{{{{ value_json.disk_io.{key}.read_ops if value_json.disk_io.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a disk write operations sensor
+    fn disk_write_ops(entity: &str, device: &str) -> DeviceComponent {
+        let key = key(device);
+        DeviceComponent {
+            name: Some(format!("{device} Disk write operations")),
+            platform: "sensor",
+            device_class: None,
+            state_class: Some("measurement"),
+            icon: Some("mdi:harddisk"),
+            unit_of_measurement: Some("ops/s".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_disk_write_ops"),
+            value_template: format!(
+                "{{{{ value_json.disk_io.{key}.write_ops if value_json.disk_io.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a watched process running sensor
+    fn process_running(entity: &str, name: &str) -> DeviceComponent {
+        let key = key(name);
+        DeviceComponent {
+            name: Some(format!("{name} running")),
+            platform: "binary_sensor",
+            device_class: Some("running".into()),
+            state_class: None,
+            icon: None,
+            unit_of_measurement: None,
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_process_running"),
+            value_template: format!(
+                "{{{{ value_json.process.{key}.running if value_json.process.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a watched process CPU usage sensor
+    fn process_cpu_usage(entity: &str, name: &str) -> DeviceComponent {
+        let key = key(name);
+        DeviceComponent {
+            name: Some(format!("{name} CPU usage")),
+            platform: "sensor",
+            device_class: None,
+            state_class: Some("measurement"),
+            icon: Some("mdi:cpu-64-bit"),
+            unit_of_measurement: Some("%".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_process_cpu_usage"),
+            value_template: format!(
+                "{{{{ value_json.process.{key}.cpu_usage if value_json.process.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a watched process memory sensor
+    fn process_memory(entity: &str, name: &str) -> DeviceComponent {
+        let key = key(name);
+        DeviceComponent {
+            name: Some(format!("{name} memory")),
+            platform: "sensor",
+            device_class: Some("data_size".into()),
+            state_class: Some("measurement"),
+            icon: Some("mdi:memory"),
+            unit_of_measurement: Some("B".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_process_memory"),
+            value_template: format!(
+                "{{{{ value_json.process.{key}.memory if value_json.process.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a systemd unit active state sensor
+    fn unit_active_state(entity: &str, unit: &str) -> DeviceComponent {
+        let key = key(unit);
+        DeviceComponent {
+            name: Some(format!("{unit} state")),
+            platform: "sensor",
+            device_class: Some("enum".into()),
+            state_class: None,
+            icon: Some("mdi:cog"),
+            unit_of_measurement: None,
+            options: Some(ACTIVE_STATES),
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_active_state"),
+            value_template: format!(
+                "{{{{ value_json.systemd.{key}.active_state if value_json.systemd.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a systemd unit sub state sensor
+    fn unit_sub_state(entity: &str, unit: &str) -> DeviceComponent {
+        let key = key(unit);
+        DeviceComponent {
+            name: Some(format!("{unit} sub state")),
+            platform: "sensor",
+            device_class: None,
+            state_class: None,
+            icon: Some("mdi:cog"),
+            unit_of_measurement: None,
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_sub_state"),
+            value_template: format!(
+                "{{{{ value_json.systemd.{key}.sub_state if value_json.systemd.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a systemd unit failure sensor
+    fn unit_failed(entity: &str, unit: &str) -> DeviceComponent {
+        let key = key(unit);
+        DeviceComponent {
+            name: Some(format!("{unit} failed")),
+            platform: "binary_sensor",
+            device_class: Some("problem".into()),
+            state_class: None,
+            icon: None,
+            unit_of_measurement: None,
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_failed"),
+            value_template: format!(
+                "{{{{ value_json.systemd.{key}.failed if value_json.systemd.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a software RAID problem sensor
+    ///
+    /// A missing array is reported as a problem
+    fn raid_problem(entity: &str, array: &str) -> DeviceComponent {
+        let key = key(array);
+        DeviceComponent {
+            name: Some(format!("{array} RAID problem")),
+            platform: "binary_sensor",
+            device_class: Some("problem".into()),
+            state_class: None,
+            icon: None,
+            unit_of_measurement: None,
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_raid_problem"),
+            value_template: format!(
+                "{{{{ value_json.raid.{key}.problem if value_json.raid.{key} else 'ON' }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a software RAID resync progress sensor
+    fn raid_sync_progress(entity: &str, array: &str) -> DeviceComponent {
+        let key = key(array);
+        DeviceComponent {
+            name: Some(format!("{array} RAID sync progress")),
+            platform: "sensor",
+            device_class: None,
+            state_class: Some("measurement"),
+            icon: Some("mdi:sync"),
+            unit_of_measurement: Some("%".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_raid_sync_progress"),
+            value_template: format!(
+                "{{{{ value_json.raid.{key}.sync_progress if value_json.raid.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a ZFS pool problem sensor
+    ///
+    /// A missing pool is reported as a problem
+    fn zfs_problem(entity: &str, pool: &str) -> DeviceComponent {
+        let key = key(pool);
+        DeviceComponent {
+            name: Some(format!("{pool} ZFS pool problem")),
+            platform: "binary_sensor",
+            device_class: Some("problem".into()),
+            state_class: None,
+            icon: None,
+            unit_of_measurement: None,
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_zfs_problem"),
+            value_template: format!(
+                "{{{{ value_json.zfs.{key}.problem if value_json.zfs.{key} else 'ON' }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a ZFS pool health sensor
+    fn zfs_health(entity: &str, pool: &str) -> DeviceComponent {
+        let key = key(pool);
+        DeviceComponent {
+            name: Some(format!("{pool} ZFS pool health")),
+            platform: "sensor",
+            device_class: Some("enum".into()),
+            state_class: None,
+            icon: Some("mdi:database"),
+            unit_of_measurement: None,
+            options: Some(ZFS_HEALTHS),
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_zfs_health"),
+            value_template: format!(
+                "{{{{ value_json.zfs.{key}.health if value_json.zfs.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a ZFS pool capacity sensor
+    fn zfs_capacity(entity: &str, pool: &str) -> DeviceComponent {
+        let key = key(pool);
+        DeviceComponent {
+            name: Some(format!("{pool} ZFS pool capacity")),
+            platform: "sensor",
+            device_class: None,
+            state_class: Some("measurement"),
+            icon: Some("mdi:database"),
+            unit_of_measurement: Some("%".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_zfs_capacity"),
+            value_template: format!(
+                "{{{{ value_json.zfs.{key}.capacity if value_json.zfs.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a ZFS pool scrub or resilver progress sensor
+    fn zfs_scan_progress(entity: &str, pool: &str) -> DeviceComponent {
+        let key = key(pool);
+        DeviceComponent {
+            name: Some(format!("{pool} ZFS pool scan progress")),
+            platform: "sensor",
+            device_class: None,
+            state_class: Some("measurement"),
+            icon: Some("mdi:sync"),
+            unit_of_measurement: Some("%".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_zfs_scan_progress"),
+            value_template: format!(
+                "{{{{ value_json.zfs.{key}.scan_progress if value_json.zfs.{key} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a custom command sensor
+    ///
+    /// The value is considered as a measurement if it has a unit
+    fn command(
+        entity: &str,
+        name: &str,
+        unit: Option<String>,
+        device_class: Option<String>,
+    ) -> DeviceComponent {
+        let key = key(name);
+        DeviceComponent {
+            name: Some(name.to_string()),
+            platform: "sensor",
+            icon: if device_class.is_none() {
+                Some("mdi:console")
+            } else {
+                None
+            },
+            device_class: device_class.map(Cow::Owned),
+            state_class: unit.as_ref().map(|_| "measurement"),
+            unit_of_measurement: unit.map(Cow::Owned),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_command"),
+            value_template: format!(
+                "{{{{ value_json.command.{key} if value_json.command.{key} is defined else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a file sensor
+    ///
+    /// The value is considered as a measurement if it has a unit
+    fn file(
+        entity: &str,
+        name: &str,
+        unit: Option<String>,
+        device_class: Option<String>,
+    ) -> DeviceComponent {
+        let key = key(name);
+        DeviceComponent {
+            name: Some(name.to_string()),
+            platform: "sensor",
+            icon: if device_class.is_none() {
+                Some("mdi:file-document")
+            } else {
+                None
+            },
+            device_class: device_class.map(Cow::Owned),
+            state_class: unit.as_ref().map(|_| "measurement"),
+            unit_of_measurement: unit.map(Cow::Owned),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_{key}_file"),
+            value_template: format!(
+                "{{{{ value_json.file.{key} if value_json.file.{key} is defined else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a battery level sensor
+    fn battery_level(entity: &str) -> DeviceComponent {
+        DeviceComponent {
+            name: Some("Battery level".to_string()),
+            platform: "sensor",
+            device_class: Some("battery".into()),
+            state_class: Some("measurement"),
+            icon: None,
+            unit_of_measurement: Some("%".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_battery_level"),
+            value_template: "{{ value_json.battery_level }}".to_string(),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a battery charging sensor
+    fn battery_charging(entity: &str) -> DeviceComponent {
+        DeviceComponent {
+            name: Some("Battery charging".to_string()),
+            platform: "binary_sensor",
+            device_class: Some("battery_charging".into()),
+            state_class: None,
+            icon: None,
+            unit_of_measurement: None,
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_battery_charging"),
+            value_template: "{{ value_json.battery_charging }}".to_string(),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates an AC adapter sensor
+    fn ac_connected(entity: &str) -> DeviceComponent {
+        DeviceComponent {
+            name: Some("AC adapter".to_string()),
+            platform: "binary_sensor",
+            device_class: Some("plug".into()),
+            state_class: None,
+            icon: None,
+            unit_of_measurement: None,
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_ac_connected"),
+            value_template: "{{ value_json.ac_connected }}".to_string(),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a load average sensor over the specified number of minutes
+    fn load_average(entity: &str, minutes: u8) -> DeviceComponent {
+        DeviceComponent {
+            name: Some(format!("Load average ({minutes} min)")),
+            platform: "sensor",
+            device_class: None,
+            state_class: Some("measurement"),
+            icon: Some("mdi:gauge"),
+            unit_of_measurement: None,
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_load_average_{minutes}"),
+            value_template: format!("{{{{ value_json.load_average_{minutes} }}}}"),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a Pressure Stall Information sensor
+    ///
+    /// The field is made of the line and the window, for example `some_avg10`
+    fn pressure(entity: &str, resource: &str, field: &str) -> DeviceComponent {
+        let resource_name = match resource {
+            "cpu" => "CPU",
+            "memory" => "Memory",
+            "io" => "I/O",
+            other => other,
+        };
+        let (line, window) = field.split_once("_avg").unwrap_or((field, ""));
+
+        DeviceComponent {
+            name: Some(format!("{resource_name} pressure ({line}, {window} s)")),
+            platform: "sensor",
+            device_class: None,
+            state_class: Some("measurement"),
+            icon: Some("mdi:gauge"),
+            unit_of_measurement: Some("%".into()),
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_pressure_{resource}_{field}"),
+            value_template: format!(
+                "{{{{ value_json.pressure.{resource}.{field} if value_json.pressure.{resource} else None }}}}"
+            ),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates an uptime sensor, reported as the boot time
+    fn uptime(entity: &str) -> DeviceComponent {
+        DeviceComponent {
+            name: Some("Up since".to_string()),
+            platform: "sensor",
+            device_class: Some("timestamp".into()),
+            state_class: None,
+            icon: None,
+            unit_of_measurement: None,
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_uptime"),
+            value_template: "{{ value_json.boot_time }}".to_string(),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a reboot required sensor
+    fn reboot_required(entity: &str) -> DeviceComponent {
+        DeviceComponent {
+            name: Some("Reboot required".to_string()),
+            platform: "binary_sensor",
+            device_class: None,
+            state_class: None,
+            icon: Some("mdi:restart-alert"),
+            unit_of_measurement: None,
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_reboot_required"),
+            value_template: "{{ value_json.reboot_required }}".to_string(),
+            expire_after: Some(60),
+        }
+    }
+
+    /// Manually creates a pending updates sensor
+    fn pending_updates(entity: &str) -> DeviceComponent {
+        DeviceComponent {
+            name: Some("Pending updates".to_string()),
+            platform: "sensor",
+            device_class: None,
+            state_class: Some("measurement"),
+            icon: Some("mdi:package-up"),
+            unit_of_measurement: None,
+            options: None,
+            entity_category: None,
+            state_topic: None,
+            availability: None,
+            unique_id: format!("{entity}_pending_updates"),
+            value_template: "{{ value_json.pending_updates }}".to_string(),
+            expire_after: Some(60),
+        }
+    }
+
+    pub fn value_template(&self) -> &str {
+        &self.value_template
+    }
+
+    /// Returns the topic of the values, if it is not the state topic of the device
+    pub fn state_topic(&self) -> Option<&str> {
+        self.state_topic.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DeviceComponent;
+    use crate::home_assistant::{RegistrationDescriptor, Sensor};
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_registration() {
+        let name = "Test Entity";
+        let entity = "test_entity";
+        let mut descriptor = RegistrationDescriptor::new(name);
+
+        Sensor::iter().for_each(|sensor| descriptor.add_component(sensor));
+
+        assert_eq!(descriptor.device.name, name);
+        assert_eq!(descriptor.device.identifiers, entity);
+
+        assert_eq!(
+            descriptor.state_topic,
+            "mqtt-system-monitor/test_entity/state"
+        );
+        assert_eq!(
+            descriptor.availability_topic(),
+            "mqtt-system-monitor/test_entity/availability"
+        );
+
+        for component in &descriptor.components {
+            assert_eq!(component.1.unique_id, format!("{entity}_{}", component.0));
+            if component.1.name.is_some() {
+                match component.1.platform {
+                    // Binary sensors report states, not measurements
+                    "binary_sensor" => assert_eq!(component.1.state_class, None),
+                    _ if component.1.unit_of_measurement.is_some() => assert!(matches!(
+                        component.1.state_class,
+                        Some("measurement" | "total_increasing")
+                    )),
+                    _ => {}
+                }
+            }
+        }
+
+        let cpu_usage = descriptor
+            .components
+            .get("cpu_usage")
+            .expect("component cpu_usage not found");
+
+        assert_eq!(cpu_usage.device_class, None);
+        assert_eq!(cpu_usage.state_topic, None);
+
+        let available = descriptor
+            .components
+            .get("available")
+            .expect("component available not found");
+
+        assert_eq!(
+            available.state_topic.as_deref(),
+            Some(descriptor.availability_topic())
+        );
+        assert_eq!(available.availability.map(|topics| topics.len()), Some(0));
+        assert!(cpu_usage.availability.is_none());
+
+        let uptime = descriptor
+            .components
+            .get("uptime")
+            .expect("component uptime not found");
+
+        assert_eq!(uptime.device_class.as_deref(), Some("timestamp"));
+        assert_eq!(uptime.state_class, None);
+
+        let net_mac = descriptor
+            .components
+            .get("_net_mac")
+            .expect("component _net_mac not found");
+
+        assert_eq!(net_mac.entity_category, Some("diagnostic"));
+
+        let net_rx_total = descriptor
+            .components
+            .get("_net_rx_total")
+            .expect("component _net_rx_total not found");
+
+        assert_eq!(net_rx_total.state_class, Some("total_increasing"));
+    }
+
+    /// Test that all sensors can be created
     #[test]
     fn test_sensors() {
         let entity = "test_entity";
@@ -372,4 +1848,33 @@ mod tests {
             assert_eq!(component.unique_id, format!("{entity}_{name}"));
         }
     }
+
+    /// Test that the custom sensors are described with their unit and device class
+    #[test]
+    fn test_custom_sensors() {
+        let command = DeviceComponent::new(
+            Sensor::Command {
+                name: "UPS charge".to_string(),
+                unit: Some("%".to_string()),
+                device_class: Some("battery".to_string()),
+            },
+            "test_entity",
+        );
+        assert_eq!(command.unit_of_measurement.as_deref(), Some("%"));
+        assert_eq!(command.device_class.as_deref(), Some("battery"));
+        assert_eq!(command.state_class, Some("measurement"));
+        assert_eq!(command.icon, None);
+
+        let file = DeviceComponent::new(
+            Sensor::File {
+                name: "Status".to_string(),
+                unit: None,
+                device_class: None,
+            },
+            "test_entity",
+        );
+        assert_eq!(file.unit_of_measurement, None);
+        assert_eq!(file.device_class, None);
+        assert_eq!(file.state_class, None);
+    }
 }
@@ -0,0 +1,96 @@
+use std::path::Path;
+
+/// Default location of the hardware monitors in sysfs
+pub const HWMON_ROOT: &str = "/sys/class/hwmon";
+
+/// Reads the value of a hardware monitor input
+///
+/// The ID is made of the monitor and the input, for example `hwmon1_fan1` for
+/// `/sys/class/hwmon/hwmon1/fan1_input`. Fan speeds are in RPM, voltages (`inN` inputs) are
+/// converted from mV to V.
+///
+/// See <https://www.kernel.org/doc/html/latest/hwmon/sysfs-interface.html> for the format
+///
+/// ## Example
+///
+/// ```no_run
+/// use mqtt_system_monitor::hwmon::{self, HWMON_ROOT};
+/// use std::path::Path;
+///
+/// let speed = hwmon::read(Path::new(HWMON_ROOT), "hwmon1_fan1");
+/// ```
+pub fn read(root: &Path, id: &str) -> Option<f32> {
+    let (monitor, input) = id.split_once('_')?;
+    let value = std::fs::read_to_string(root.join(monitor).join(format!("{input}_input")))
+        .ok()?
+        .trim()
+        .parse::<f32>()
+        .ok()?;
+
+    if input.starts_with("in") {
+        Some(value / 1000.0)
+    } else {
+        Some(value)
+    }
+}
+
+/// Returns the label of a hardware monitor input
+///
+/// The label is made of the name of the monitor and the label of the input, or the input itself
+/// if the monitor doesn't provide a label
+pub fn label(root: &Path, id: &str) -> String {
+    let Some((monitor, input)) = id.split_once('_') else {
+        return id.to_string();
+    };
+
+    let attribute = |name: &str| {
+        std::fs::read_to_string(root.join(monitor).join(name))
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    let name = attribute("name").unwrap_or_else(|| monitor.to_string());
+    let label = attribute(&format!("{input}_label")).unwrap_or_else(|| input.to_string());
+
+    format!("{name} {label}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let root =
+            std::env::temp_dir().join(format!("mqtt-system-monitor-hwmon-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("hwmon1")).expect("Cannot create monitor");
+        for (file, value) in [
+            ("name", "nct6775"),
+            ("fan1_input", "1250"),
+            ("fan2_input", "0"),
+            ("fan2_label", "CPU fan"),
+            ("in0_input", "1104"),
+        ] {
+            std::fs::write(root.join("hwmon1").join(file), format!("{value}\n"))
+                .expect("Cannot write attribute");
+        }
+
+        let fan1 = read(&root, "hwmon1_fan1");
+        let fan2 = read(&root, "hwmon1_fan2");
+        let in0 = read(&root, "hwmon1_in0");
+        let missing = read(&root, "hwmon1_fan3");
+        let invalid = read(&root, "hwmon1");
+        let fan1_label = label(&root, "hwmon1_fan1");
+        let fan2_label = label(&root, "hwmon1_fan2");
+        std::fs::remove_dir_all(&root).expect("Cannot remove fake sysfs");
+
+        assert_eq!(fan1, Some(1250.0));
+        assert_eq!(fan2, Some(0.0));
+        assert_eq!(in0, Some(1.104));
+        assert_eq!(missing, None);
+        assert_eq!(invalid, None);
+        assert_eq!(fan1_label, "nct6775 fan1");
+        assert_eq!(fan2_label, "nct6775 CPU fan");
+    }
+}
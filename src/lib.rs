@@ -13,11 +13,43 @@ pub use self::home_assistant::RegistrationDescriptor;
 pub use self::home_assistant::Sensor;
 pub use self::status::StatusMessage;
 
+/// Contains the custom command runner
+pub mod command;
 /// Contains the configuration stuff
 pub mod configuration;
 /// Contains the daemon code
 pub mod daemon;
+/// Contains the block device statistics reader
+pub mod diskstats;
+/// Contains the file sensor reader
+pub mod file;
 /// Contains Home Assistant registration data
 pub mod home_assistant;
+/// Contains the hardware monitor reader
+pub mod hwmon;
+/// Contains the software RAID status reader
+pub mod mdstat;
+/// Contains the memory statistics reader
+pub mod meminfo;
+/// Contains the network interfaces reader
+pub mod net;
+/// Contains the battery and AC adapter reader
+pub mod power_supply;
+/// Contains the Pressure Stall Information reader
+pub mod pressure;
 /// Contains the status that is sent to MQTT
 pub mod status;
+/// Contains the filesystem statistics reader
+pub mod statvfs;
+/// Contains the systemd units state reader
+pub mod systemd;
+/// Contains the TLS configuration of the broker connection
+pub mod tls;
+/// Contains the pending updates and reboot readers
+pub mod updates;
+/// Contains the parser of the custom sensor values
+pub mod value;
+/// Contains the wireless statistics reader
+pub mod wireless;
+/// Contains the ZFS pools state reader
+pub mod zfs;
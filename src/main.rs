@@ -22,5 +22,8 @@ async fn main() {
         .init()
         .expect("Failed to initialize logging");
 
-    Daemon::new(config).run().await;
+    Daemon::new(config)
+        .expect("Failed to create the daemon")
+        .run()
+        .await;
 }
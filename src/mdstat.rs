@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+/// Path of the software RAID status provided by the kernel
+pub const MDSTAT_PATH: &str = "/proc/mdstat";
+
+/// State of a software RAID array
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MdArray {
+    /// `true` if the array is active
+    pub active: bool,
+
+    /// `true` if a member of the array is missing or faulty
+    pub degraded: bool,
+
+    /// Progress in % of the running resync, recovery, check or reshape, if any
+    pub sync_progress: Option<f32>,
+}
+
+/// Reads the state of all the software RAID arrays
+///
+/// Returns an empty map if the status is not available
+pub fn read() -> HashMap<String, MdArray> {
+    std::fs::read_to_string(MDSTAT_PATH)
+        .map(|content| parse(&content))
+        .unwrap_or_default()
+}
+
+/// Parses the content of `/proc/mdstat`
+///
+/// See <https://raid.wiki.kernel.org/index.php/Mdstat> for the format
+///
+/// ## Example
+///
+/// ```
+/// use mqtt_system_monitor::mdstat;
+///
+/// let arrays = mdstat::parse(
+///     "md0 : active raid1 sdb1[1] sda1[0]\n      976630464 blocks super 1.2 [2/1] [U_]\n",
+/// );
+///
+/// assert!(arrays["md0"].active);
+/// assert!(arrays["md0"].degraded);
+/// ```
+pub fn parse(content: &str) -> HashMap<String, MdArray> {
+    let mut map = HashMap::new();
+    let mut current = None;
+
+    for line in content.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            current = None;
+            let Some((name, description)) = line.split_once(" : ") else {
+                continue;
+            };
+            if name == "Personalities" {
+                continue;
+            }
+
+            let mut fields = description.split_whitespace();
+            let array = MdArray {
+                active: fields.next() == Some("active"),
+                degraded: fields.any(|device| device.ends_with("(F)")),
+                sync_progress: None,
+            };
+            current = Some(name.trim().to_string());
+            map.insert(name.trim().to_string(), array);
+            continue;
+        }
+
+        let Some(array) = current.as_ref().and_then(|name| map.get_mut(name)) else {
+            continue;
+        };
+
+        // Members status, such as `[UU_]`, where `_` is a missing member
+        if let Some(members) = line.split_whitespace().last()
+            && let Some(members) = members.strip_prefix('[').and_then(|m| m.strip_suffix(']'))
+            && !members.is_empty()
+            && members.chars().all(|c| c == 'U' || c == '_')
+        {
+            array.degraded |= members.contains('_');
+        }
+
+        // Sync status, such as `[=>....]  recovery =  8.6% (84253696/976630272) finish=96.2min`
+        if let Some((_, progress)) = line.split_once(" = ")
+            && let Some((progress, _)) = progress.trim_start().split_once('%')
+        {
+            array.sync_progress = progress.parse::<f32>().ok();
+        }
+    }
+
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let content = "\
+Personalities : [raid1] [raid6] [raid5] [raid4] [raid0]
+md0 : active raid1 sdb1[1] sda1[0]
+      976630464 blocks super 1.2 [2/2] [UU]
+      bitmap: 0/8 pages [0KB], 65536KB chunk
+
+md1 : active raid5 sdd1[3] sdc1[1] sdb2[0]
+      1953260544 blocks super 1.2 level 5, 512k chunk, algorithm 2 [3/2] [UU_]
+      [=>...................]  recovery =  8.6% (84253696/976630272) finish=96.2min speed=154612K/sec
+
+md2 : inactive sde1[0](S)
+      976630488 blocks super 1.2
+
+md3 : active raid0 sdg1[1](F) sdf1[0]
+      1953260544 blocks super 1.2 512k chunks
+
+md4 : active raid1 sdi1[1] sdh1[0]
+      976630464 blocks super 1.2 [2/2] [UU]
+      [==========>..........]  check = 52.1% (508860928/976630464) finish=40.1min speed=194216K/sec
+
+unused devices: <none>
+";
+        let arrays = parse(content);
+
+        assert_eq!(arrays.len(), 5);
+        assert_eq!(
+            arrays["md0"],
+            MdArray {
+                active: true,
+                degraded: false,
+                sync_progress: None,
+            }
+        );
+        assert_eq!(
+            arrays["md1"],
+            MdArray {
+                active: true,
+                degraded: true,
+                sync_progress: Some(8.6),
+            }
+        );
+        assert!(!arrays["md2"].active);
+        assert!(arrays["md3"].degraded);
+        assert!(!arrays["md4"].degraded);
+        assert_eq!(arrays["md4"].sync_progress, Some(52.1));
+    }
+}
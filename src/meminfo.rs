@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+/// Path of the memory statistics provided by the kernel
+pub const MEMINFO_PATH: &str = "/proc/meminfo";
+
+/// Reads the memory statistics, in bytes
+///
+/// Returns an empty map if the statistics are not available
+pub fn read() -> HashMap<String, u64> {
+    std::fs::read_to_string(MEMINFO_PATH)
+        .map(|content| parse(&content))
+        .unwrap_or_default()
+}
+
+/// Parses the content of `/proc/meminfo`, converting the values in bytes
+///
+/// ## Example
+///
+/// ```
+/// use mqtt_system_monitor::meminfo;
+///
+/// let stats = meminfo::parse("Cached:          1935692 kB");
+///
+/// assert_eq!(stats["Cached"], 1935692 * 1024);
+/// ```
+pub fn parse(content: &str) -> HashMap<String, u64> {
+    let mut map = HashMap::new();
+
+    for line in content.lines() {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+
+        let mut fields = value.split_whitespace();
+        let Some(Ok(value)) = fields.next().map(|v| v.parse::<u64>()) else {
+            continue;
+        };
+
+        let value = match fields.next() {
+            Some("kB") => value * 1024,
+            _ => value,
+        };
+
+        map.insert(name.to_string(), value);
+    }
+
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let content = "MemTotal:        6147400 kB
+MemFree:         3752076 kB
+MemAvailable:    5558300 kB
+Buffers:           66968 kB
+Cached:          1935692 kB
+HugePages_Total:       0
+invalid line
+";
+        let stats = parse(content);
+
+        assert_eq!(stats.len(), 6);
+        assert_eq!(stats["MemAvailable"], 5558300 * 1024);
+        assert_eq!(stats["Buffers"], 66968 * 1024);
+        assert_eq!(stats["HugePages_Total"], 0);
+    }
+}
@@ -0,0 +1,123 @@
+use std::path::Path;
+
+/// Location of the network interfaces in sysfs
+pub const NET_ROOT: &str = "/sys/class/net";
+
+/// Cumulative counters of dropped packets of a network interface
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Drops {
+    /// Number of dropped received packets
+    pub rx: u64,
+
+    /// Number of dropped transmitted packets
+    pub tx: u64,
+}
+
+/// Tells if the link of a network interface is up
+///
+/// Interfaces reporting an `unknown` operational state, such as the loopback or some tunnels,
+/// are up if they have a carrier.
+///
+/// See <https://www.kernel.org/doc/Documentation/ABI/testing/sysfs-class-net> for the format
+pub fn link_up(root: &Path, interface: &str) -> bool {
+    let attribute = |name: &str| {
+        std::fs::read_to_string(root.join(interface).join(name))
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    match attribute("operstate").as_str() {
+        "up" => true,
+        "unknown" => attribute("carrier") == "1",
+        _ => false,
+    }
+}
+
+/// Tells if a network interface is a wireless interface
+pub fn is_wireless(root: &Path, interface: &str) -> bool {
+    root.join(interface).join("wireless").is_dir()
+}
+
+/// Reads the counters of dropped packets of a network interface
+///
+/// Returns `None` if the interface doesn't exist
+pub fn drops(root: &Path, interface: &str) -> Option<Drops> {
+    let statistics = root.join(interface).join("statistics");
+    if !statistics.is_dir() {
+        return None;
+    }
+
+    let statistic = |name: &str| {
+        std::fs::read_to_string(statistics.join(name))
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or_default()
+    };
+
+    Some(Drops {
+        rx: statistic("rx_dropped"),
+        tx: statistic("tx_dropped"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_up() {
+        let root =
+            std::env::temp_dir().join(format!("mqtt-system-monitor-net-{}", std::process::id()));
+        for (interface, operstate, carrier) in [
+            ("eth0", "up", "1"),
+            ("eth1", "down", "0"),
+            ("lo", "unknown", "1"),
+            ("tun0", "unknown", "0"),
+        ] {
+            std::fs::create_dir_all(root.join(interface)).expect("Cannot create interface");
+            std::fs::write(
+                root.join(interface).join("operstate"),
+                format!("{operstate}\n"),
+            )
+            .expect("Cannot write operstate");
+            std::fs::write(root.join(interface).join("carrier"), format!("{carrier}\n"))
+                .expect("Cannot write carrier");
+        }
+
+        let states: Vec<bool> = ["eth0", "eth1", "lo", "tun0", "missing"]
+            .iter()
+            .map(|interface| link_up(&root, interface))
+            .collect();
+
+        std::fs::create_dir_all(root.join("wlan0").join("wireless"))
+            .expect("Cannot create wireless interface");
+        let wireless = [
+            is_wireless(&root, "wlan0"),
+            is_wireless(&root, "eth0"),
+            is_wireless(&root, "missing"),
+        ];
+        std::fs::remove_dir_all(&root).expect("Cannot remove fake sysfs");
+
+        assert_eq!(states, vec![true, false, true, false, false]);
+        assert_eq!(wireless, [true, false, false]);
+    }
+
+    #[test]
+    fn test_drops() {
+        let root = std::env::temp_dir().join(format!(
+            "mqtt-system-monitor-net-drops-{}",
+            std::process::id()
+        ));
+        let statistics = root.join("eth0").join("statistics");
+        std::fs::create_dir_all(&statistics).expect("Cannot create statistics");
+        std::fs::write(statistics.join("rx_dropped"), "12\n").expect("Cannot write rx_dropped");
+        std::fs::write(statistics.join("tx_dropped"), "3\n").expect("Cannot write tx_dropped");
+
+        let eth0 = drops(&root, "eth0");
+        let missing = drops(&root, "missing");
+        std::fs::remove_dir_all(&root).expect("Cannot remove fake sysfs");
+
+        assert_eq!(eth0, Some(Drops { rx: 12, tx: 3 }));
+        assert_eq!(missing, None);
+    }
+}
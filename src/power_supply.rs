@@ -0,0 +1,112 @@
+use std::path::Path;
+
+/// Default location of the power supplies in sysfs
+pub const POWER_SUPPLY_ROOT: &str = "/sys/class/power_supply";
+
+/// State of the battery and the AC adapter
+#[derive(Debug, Default, PartialEq)]
+pub struct PowerSupplyState {
+    /// Charge level of the first system battery in %, if any
+    pub battery_level: Option<f32>,
+
+    /// `true` if the first system battery is charging, if any
+    pub battery_charging: Option<bool>,
+
+    /// `true` if an AC adapter is plugged, if any
+    pub ac_connected: Option<bool>,
+}
+
+/// Reads the state of the power supplies found in `root`
+///
+/// Batteries of peripherals, such as mice or keyboards, are ignored.
+///
+/// See <https://www.kernel.org/doc/Documentation/ABI/testing/sysfs-class-power> for the format
+pub fn read(root: &Path) -> PowerSupplyState {
+    let mut state = PowerSupplyState::default();
+
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return state;
+    };
+    let mut supplies: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+    supplies.sort();
+
+    for supply in supplies {
+        let attribute = |name: &str| {
+            std::fs::read_to_string(supply.join(name))
+                .map(|value| value.trim().to_string())
+                .ok()
+        };
+
+        match attribute("type").as_deref() {
+            Some("Battery")
+                if state.battery_level.is_none()
+                    && attribute("scope").as_deref() != Some("Device") =>
+            {
+                state.battery_level = attribute("capacity").and_then(|c| c.parse().ok());
+                state.battery_charging = attribute("status").map(|s| s == "Charging");
+            }
+            Some("Mains") => {
+                let online = attribute("online").as_deref() == Some("1");
+                state.ac_connected = Some(state.ac_connected.unwrap_or_default() || online);
+            }
+            _ => {}
+        }
+    }
+
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_supply(root: &Path, name: &str, attributes: &[(&str, &str)]) {
+        std::fs::create_dir_all(root.join(name)).expect("Cannot create supply");
+        for (attribute, value) in attributes {
+            std::fs::write(root.join(name).join(attribute), format!("{value}\n"))
+                .expect("Cannot write attribute");
+        }
+    }
+
+    #[test]
+    fn test_read() {
+        let root = std::env::temp_dir().join(format!(
+            "mqtt-system-monitor-power-supply-{}",
+            std::process::id()
+        ));
+        write_supply(&root, "AC", &[("type", "Mains"), ("online", "0")]);
+        write_supply(
+            &root,
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("capacity", "42"),
+                ("status", "Charging"),
+            ],
+        );
+        write_supply(
+            &root,
+            "hidpp_battery_0",
+            &[("type", "Battery"), ("scope", "Device"), ("capacity", "5")],
+        );
+        write_supply(
+            &root,
+            "ucsi-source-psy-1",
+            &[("type", "USB"), ("online", "1")],
+        );
+
+        let state = read(&root);
+        let missing = read(&root.join("missing"));
+        std::fs::remove_dir_all(&root).expect("Cannot remove fake sysfs");
+
+        assert_eq!(
+            state,
+            PowerSupplyState {
+                battery_level: Some(42.0),
+                battery_charging: Some(true),
+                ac_connected: Some(false),
+            }
+        );
+        assert_eq!(missing, PowerSupplyState::default());
+    }
+}
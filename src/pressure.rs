@@ -0,0 +1,116 @@
+use serde::Serialize;
+use std::path::Path;
+
+/// Location of the Pressure Stall Information provided by the kernel
+pub const PRESSURE_ROOT: &str = "/proc/pressure";
+
+/// Resources reported by the Pressure Stall Information
+pub const RESOURCES: [&str; 3] = ["cpu", "memory", "io"];
+
+/// Pressure Stall Information of a resource
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct Pressure {
+    /// Share of time in % some tasks were stalled, over the last 10 seconds
+    pub some_avg10: f32,
+
+    /// Share of time in % some tasks were stalled, over the last 60 seconds
+    pub some_avg60: f32,
+
+    /// Share of time in % all the tasks were stalled, over the last 10 seconds, if reported
+    pub full_avg10: Option<f32>,
+
+    /// Share of time in % all the tasks were stalled, over the last 60 seconds, if reported
+    pub full_avg60: Option<f32>,
+}
+
+/// Reads the pressure of a resource, such as `cpu`, `memory` or `io`
+///
+/// Returns `None` if the kernel doesn't provide the Pressure Stall Information
+pub fn read(root: &Path, resource: &str) -> Option<Pressure> {
+    parse(&std::fs::read_to_string(root.join(resource)).ok()?)
+}
+
+/// Parses the content of a `/proc/pressure` file
+///
+/// See <https://docs.kernel.org/accounting/psi.html> for the format
+///
+/// ## Example
+///
+/// ```
+/// use mqtt_system_monitor::pressure;
+///
+/// let pressure = pressure::parse("some avg10=1.50 avg60=0.75 avg300=0.20 total=1234").unwrap();
+///
+/// assert_eq!(pressure.some_avg10, 1.5);
+/// assert_eq!(pressure.full_avg10, None);
+/// ```
+pub fn parse(content: &str) -> Option<Pressure> {
+    let mut some = None;
+    let mut full = None;
+
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let kind = fields.next();
+
+        let mut avg10 = None;
+        let mut avg60 = None;
+        for field in fields {
+            match field.split_once('=') {
+                Some(("avg10", value)) => avg10 = value.parse::<f32>().ok(),
+                Some(("avg60", value)) => avg60 = value.parse::<f32>().ok(),
+                _ => {}
+            }
+        }
+
+        let (Some(avg10), Some(avg60)) = (avg10, avg60) else {
+            continue;
+        };
+        match kind {
+            Some("some") => some = Some((avg10, avg60)),
+            Some("full") => full = Some((avg10, avg60)),
+            _ => {}
+        }
+    }
+
+    let (some_avg10, some_avg60) = some?;
+    Some(Pressure {
+        some_avg10,
+        some_avg60,
+        full_avg10: full.map(|(avg10, _)| avg10),
+        full_avg60: full.map(|(_, avg60)| avg60),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let content = "\
+some avg10=4.35 avg60=3.75 avg300=3.21 total=45126368
+full avg10=0.02 avg60=0.11 avg300=0.09 total=5275533
+";
+
+        assert_eq!(
+            parse(content),
+            Some(Pressure {
+                some_avg10: 4.35,
+                some_avg60: 3.75,
+                full_avg10: Some(0.02),
+                full_avg60: Some(0.11),
+            })
+        );
+        assert_eq!(
+            parse("some avg10=0.00 avg60=0.00 avg300=0.00 total=0\n"),
+            Some(Pressure::default())
+        );
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("invalid line"), None);
+    }
+
+    #[test]
+    fn test_read_missing() {
+        assert_eq!(read(Path::new("/nonexistent"), "cpu"), None);
+    }
+}
@@ -1,4 +1,6 @@
+use crate::pressure::Pressure;
 use serde::Serialize;
+use serde_json::Value;
 use std::collections::HashMap;
 use std::fmt;
 
@@ -7,19 +9,108 @@ use std::fmt;
 /// This contains the payload that Home Assistant uses to read the values.
 #[derive(Serialize, Debug, Default)]
 pub struct StatusMessage {
-    pub available: &'static str,
-
     /// CPU usage in %
     pub cpu_usage: Option<f32>,
 
+    /// Statistics for each logical core
+    pub cpus: Vec<CpuStatus>,
+
     /// Memory usage in %
     pub memory_usage: Option<f32>,
 
+    /// Swap usage in %
+    pub swap_usage: Option<f32>,
+
+    /// Available memory in bytes
+    pub memory_available: Option<u64>,
+
+    /// Memory used by the page cache in bytes
+    pub memory_cached: Option<u64>,
+
+    /// Memory used by the buffers in bytes
+    pub memory_buffers: Option<u64>,
+
+    /// Battery level in %
+    pub battery_level: Option<f32>,
+
+    /// `ON` if the battery is charging, `OFF` otherwise
+    pub battery_charging: Option<&'static str>,
+
+    /// `ON` if the AC adapter is plugged, `OFF` otherwise
+    pub ac_connected: Option<&'static str>,
+
+    /// Load average over the last minute
+    pub load_average_1: Option<f64>,
+
+    /// Load average over the last 5 minutes
+    pub load_average_5: Option<f64>,
+
+    /// Load average over the last 15 minutes
+    pub load_average_15: Option<f64>,
+
+    /// Boot time in RFC 3339 format
+    pub boot_time: Option<String>,
+
+    /// `ON` if a reboot is required to apply the updates, `OFF` otherwise
+    pub reboot_required: Option<&'static str>,
+
+    /// Number of pending updates
+    pub pending_updates: Option<u64>,
+
     /// Temperatures in °C
     pub temperature: HashMap<String, f32>,
 
+    /// `ON` if a temperature crossed its warning threshold, `OFF` otherwise
+    pub temperature_warning: HashMap<String, &'static str>,
+
+    /// `ON` if a temperature crossed its critical threshold, `OFF` otherwise
+    pub temperature_critical: HashMap<String, &'static str>,
+
+    /// Fan speeds in RPM
+    pub fan: HashMap<String, f32>,
+
+    /// Voltages in V
+    pub voltage: HashMap<String, f32>,
+
     /// Statistics for the network interfaces
     pub network: HashMap<String, NetworkStatus>,
+
+    /// Statistics for the mount points
+    pub disk: HashMap<String, DiskStatus>,
+
+    /// Statistics for the block devices
+    pub disk_io: HashMap<String, DiskIoStatus>,
+
+    /// Statistics for the watched processes
+    pub process: HashMap<String, ProcessStatus>,
+
+    /// State of the systemd units
+    pub systemd: HashMap<String, SystemdUnitStatus>,
+
+    /// State of the software RAID arrays
+    pub raid: HashMap<String, RaidStatus>,
+
+    /// State of the ZFS pools
+    pub zfs: HashMap<String, ZfsPoolStatus>,
+
+    /// Values of the custom command sensors
+    pub command: HashMap<String, Value>,
+
+    /// Values of the file sensors
+    pub file: HashMap<String, Value>,
+
+    /// Pressure Stall Information of each resource
+    pub pressure: HashMap<String, Pressure>,
+}
+
+/// Logical core status
+#[derive(Serialize, Debug, Default)]
+pub struct CpuStatus {
+    /// Core usage in %
+    pub usage: f32,
+
+    /// Core frequency in MHz
+    pub frequency: u64,
 }
 
 /// Network status
@@ -30,6 +121,135 @@ pub struct NetworkStatus {
 
     /// Net RX rate in KiB/s
     pub rx: f64,
+
+    /// Transmitted packets per second, if `sensors.network-packets` is set
+    pub tx_packets: Option<f64>,
+
+    /// Received packets per second, if `sensors.network-packets` is set
+    pub rx_packets: Option<f64>,
+
+    /// Transmission errors per second, if `sensors.network-packets` is set
+    pub tx_errors: Option<f64>,
+
+    /// Reception errors per second, if `sensors.network-packets` is set
+    pub rx_errors: Option<f64>,
+
+    /// Dropped transmitted packets per second, if `sensors.network-packets` is set
+    pub tx_drops: Option<f64>,
+
+    /// Dropped received packets per second, if `sensors.network-packets` is set
+    pub rx_drops: Option<f64>,
+
+    /// Total amount of transmitted data in bytes, if `sensors.network-totals` is set
+    pub tx_total: Option<u64>,
+
+    /// Total amount of received data in bytes, if `sensors.network-totals` is set
+    pub rx_total: Option<u64>,
+
+    /// Wi-Fi link quality, for wireless interfaces
+    pub wifi_link_quality: Option<f32>,
+
+    /// Wi-Fi signal level in dBm, for wireless interfaces
+    pub wifi_signal: Option<f32>,
+
+    /// Wi-Fi noise level in dBm, for wireless interfaces whose driver reports it
+    pub wifi_noise: Option<f32>,
+
+    /// `ON` if the link is up, `OFF` otherwise, if `sensors.network-details` is set
+    pub link: Option<&'static str>,
+
+    /// IPv4 addresses, separated by commas, if any and if `sensors.network-details` is set
+    pub ipv4: Option<String>,
+
+    /// IPv6 addresses, separated by commas, if any and if `sensors.network-details` is set
+    pub ipv6: Option<String>,
+
+    /// MAC address, if `sensors.network-details` is set
+    pub mac: Option<String>,
+}
+
+/// Disk status
+#[derive(Serialize, Debug, Default)]
+pub struct DiskStatus {
+    /// Disk usage in %
+    pub usage: f32,
+
+    /// Used space in bytes
+    pub used: u64,
+
+    /// Free space in bytes
+    pub free: u64,
+
+    /// Inode usage in %, if the filesystem has a fixed number of inodes
+    pub inode_usage: Option<f32>,
+}
+
+/// Disk I/O status
+#[derive(Serialize, Debug, Default)]
+pub struct DiskIoStatus {
+    /// Read rate in KiB/s
+    pub read: f64,
+
+    /// Write rate in KiB/s
+    pub write: f64,
+
+    /// Read operations per second
+    pub read_ops: f64,
+
+    /// Write operations per second
+    pub write_ops: f64,
+}
+
+/// Software RAID array status
+#[derive(Serialize, Debug, Default)]
+pub struct RaidStatus {
+    /// `ON` if the array is inactive or degraded, `OFF` otherwise
+    pub problem: &'static str,
+
+    /// Progress of the resync in %, 100 if no resync is running
+    pub sync_progress: f32,
+}
+
+/// ZFS pool status
+#[derive(Serialize, Debug, Default)]
+pub struct ZfsPoolStatus {
+    /// `ON` if the pool is not `ONLINE`, `OFF` otherwise
+    pub problem: &'static str,
+
+    /// Health of the pool, for example `ONLINE` or `DEGRADED`
+    pub health: String,
+
+    /// Used capacity of the pool in %
+    pub capacity: f32,
+
+    /// Progress of the scrub or resilver in %, 100 if no scan is running
+    pub scan_progress: f32,
+}
+
+/// Watched process status
+#[derive(Serialize, Debug, Default)]
+pub struct ProcessStatus {
+    /// `ON` if the process is running, `OFF` otherwise
+    pub running: &'static str,
+
+    /// CPU usage of the process in %, relative to a single core
+    pub cpu_usage: f32,
+
+    /// Resident memory of the process in bytes
+    pub memory: u64,
+}
+
+/// Systemd unit status
+#[derive(Serialize, Debug, Default)]
+pub struct SystemdUnitStatus {
+    /// `ActiveState` of the unit
+    pub active_state: String,
+
+    /// `SubState` of the unit
+    pub sub_state: String,
+
+    /// `ON` if the unit is in the `failed` state, `OFF` otherwise
+    pub failed: &'static str,
 }
 
 impl fmt::Display for StatusMessage {
@@ -41,13 +261,3 @@ impl fmt::Display for StatusMessage {
         write!(f, "{str}")
     }
 }
-
-impl StatusMessage {
-    /// Produces the status when we're disconnecting
-    pub fn off() -> StatusMessage {
-        StatusMessage {
-            available: "OFF",
-            ..Default::default()
-        }
-    }
-}
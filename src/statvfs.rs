@@ -0,0 +1,76 @@
+use std::ffi::CString;
+use std::io;
+use std::mem::MaybeUninit;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+/// Inode counters of a filesystem
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Inodes {
+    /// Total number of inodes
+    pub total: u64,
+
+    /// Number of free inodes
+    pub free: u64,
+}
+
+impl Inodes {
+    /// Returns the inode usage in %, or `None` if the filesystem has no fixed number of inodes,
+    /// like btrfs
+    pub fn usage(&self) -> Option<f32> {
+        (self.total > 0)
+            .then(|| 100.0 * (self.total.saturating_sub(self.free) as f32 / self.total as f32))
+    }
+}
+
+/// Reads the inode counters of the filesystem mounted at `path` with `statvfs`
+///
+/// ## Example
+///
+/// ```
+/// use mqtt_system_monitor::statvfs;
+/// use std::path::Path;
+///
+/// let inodes = statvfs::inodes(Path::new("/")).expect("Cannot read the root filesystem");
+///
+/// assert!(inodes.free <= inodes.total);
+/// ```
+pub fn inodes(path: &Path) -> io::Result<Inodes> {
+    let path = CString::new(path.as_os_str().as_bytes())?;
+    let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+
+    // SAFETY: the path is a valid C string and `stat` is only read if the call succeeded
+    let stat = unsafe {
+        if libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        stat.assume_init()
+    };
+
+    Ok(Inodes {
+        total: stat.f_files as u64,
+        free: stat.f_ffree as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage() {
+        let inodes = Inodes {
+            total: 1000,
+            free: 250,
+        };
+
+        assert_eq!(inodes.usage(), Some(75.0));
+        assert_eq!(Inodes::default().usage(), None);
+    }
+
+    #[test]
+    fn test_inodes() {
+        assert!(inodes(Path::new("/")).is_ok());
+        assert!(inodes(Path::new("/nonexistent")).is_err());
+    }
+}
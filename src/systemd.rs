@@ -0,0 +1,167 @@
+use crate::command;
+use log::warn;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Command used to query the state of the units
+pub const SYSTEMCTL: &str = "systemctl";
+
+/// Possible values of the `ActiveState` property of a unit
+pub const ACTIVE_STATES: &[&str] = &[
+    "active",
+    "reloading",
+    "inactive",
+    "failed",
+    "activating",
+    "deactivating",
+    "maintenance",
+    "refreshing",
+];
+
+/// State of a systemd unit
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct UnitState {
+    /// Value of the `ActiveState` property, for example `active` or `failed`
+    pub active_state: String,
+
+    /// Value of the `SubState` property, for example `running` or `exited`
+    pub sub_state: String,
+}
+
+/// Maximum duration of a `systemctl` query
+pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Reads the state of the units with `systemctl show`
+///
+/// Units that are not reported by `systemctl` are missing from the map. Returns an empty map if
+/// `systemctl` cannot be run or doesn't complete before [TIMEOUT].
+pub async fn read(systemctl: &str, units: &[String]) -> HashMap<String, UnitState> {
+    let mut args = vec!["show", "--property=Id,Names,ActiveState,SubState", "--"];
+    args.extend(units.iter().map(String::as_str));
+
+    let states = match command::execute(systemctl, &args, TIMEOUT).await {
+        Ok(output) => parse(&output),
+        Err(e) => {
+            warn!("Cannot query the systemd units: {e}");
+            return HashMap::new();
+        }
+    };
+
+    units
+        .iter()
+        .filter_map(|unit| Some((unit.clone(), states.get(unit)?.clone())))
+        .collect()
+}
+
+/// Parses the output of `systemctl show`, which contains one block of properties per unit
+///
+/// Each state is keyed by the `Id` of its unit and by its other `Names`, so that units configured
+/// with an alias are found.
+///
+/// ## Example
+///
+/// ```
+/// use mqtt_system_monitor::systemd;
+///
+/// let states = systemd::parse(
+///     "Id=ssh.service\nNames=ssh.service sshd.service\nActiveState=failed\nSubState=failed\n",
+/// );
+///
+/// assert_eq!(states["ssh.service"].active_state, "failed");
+/// assert_eq!(states["sshd.service"].sub_state, "failed");
+/// ```
+pub fn parse(output: &str) -> HashMap<String, UnitState> {
+    let mut states = HashMap::new();
+    for block in output.split("\n\n") {
+        let mut names = Vec::new();
+        let mut state = UnitState::default();
+        for (name, value) in block.lines().filter_map(|line| line.split_once('=')) {
+            match name {
+                "Id" => names.push(value),
+                "Names" => names.extend(value.split_whitespace()),
+                "ActiveState" => state.active_state = value.to_string(),
+                "SubState" => state.sub_state = value.to_string(),
+                _ => {}
+            }
+        }
+
+        for name in names {
+            states.insert(name.to_string(), state.clone());
+        }
+    }
+
+    states
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn test_parse() {
+        let output = "Id=nginx.service
+Names=nginx.service
+ActiveState=active
+SubState=running
+
+SubState=failed
+ActiveState=failed
+Names=ssh.service sshd.service
+Id=ssh.service
+
+Id=backup.timer
+ActiveState=inactive
+SubState=dead
+";
+        let states = parse(output);
+
+        assert_eq!(states.len(), 4);
+        assert_eq!(
+            states["nginx.service"],
+            UnitState {
+                active_state: "active".to_string(),
+                sub_state: "running".to_string(),
+            }
+        );
+        assert_eq!(states["ssh.service"].active_state, "failed");
+        assert_eq!(states["sshd.service"], states["ssh.service"]);
+        assert_eq!(states["backup.timer"].sub_state, "dead");
+    }
+
+    /// Test the query with a fake `systemctl`, which reports the units in another order
+    #[tokio::test]
+    async fn test_read() -> Result<(), Box<dyn std::error::Error>> {
+        let directory = std::env::temp_dir().join(format!(
+            "mqtt-system-monitor-systemd-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&directory)?;
+        let systemctl = directory.join("systemctl");
+        std::fs::write(
+            &systemctl,
+            "#!/bin/sh
+printf 'Id=backup.timer\\nNames=backup.timer\\nActiveState=failed\\nSubState=failed\\n\\n'
+printf 'Id=nginx.service\\nNames=nginx.service\\nActiveState=active\\nSubState=running\\n'
+",
+        )?;
+        std::fs::set_permissions(&systemctl, std::fs::Permissions::from_mode(0o755))?;
+
+        let units = vec![
+            "nginx.service".to_string(),
+            "missing.service".to_string(),
+            "backup.timer".to_string(),
+        ];
+        let states = read(systemctl.to_str().unwrap(), &units).await;
+        std::fs::remove_dir_all(&directory)?;
+
+        assert_eq!(states.len(), 2);
+        assert_eq!(states["nginx.service"].sub_state, "running");
+        assert_eq!(states["backup.timer"].active_state, "failed");
+
+        let states = read("/nonexistent/systemctl", &units).await;
+        assert!(states.is_empty());
+
+        Ok(())
+    }
+}
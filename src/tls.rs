@@ -0,0 +1,360 @@
+use crate::configuration::Tls;
+use rumqttc::tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use rumqttc::tokio_rustls::rustls::crypto::{
+    self, WebPkiSupportedAlgorithms, verify_tls12_signature, verify_tls13_signature,
+};
+use rumqttc::tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rumqttc::tokio_rustls::rustls::{
+    ClientConfig, DigitallySignedStruct, Error as TlsError, RootCertStore, SignatureScheme,
+};
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Arc;
+
+/// Builds the TLS configuration of the connection to the broker
+///
+/// The broker certificate is verified against the certificate authorities of `ca-file`, or
+/// against the certificate authorities of the system if it is not set
+///
+/// ## Example
+///
+/// ```
+/// use mqtt_system_monitor::configuration::Tls;
+/// use mqtt_system_monitor::tls;
+///
+/// let settings = Tls {
+///     alpn: vec![String::from("mqtt")],
+///     ..Default::default()
+/// };
+/// let config = tls::client_config(&settings).expect("Cannot configure TLS");
+///
+/// assert_eq!(config.alpn_protocols, vec![b"mqtt".to_vec()]);
+/// ```
+pub fn client_config(tls: &Tls) -> Result<ClientConfig, Box<dyn Error>> {
+    let builder = ClientConfig::builder().with_root_certificates(root_certificates(tls)?);
+
+    let mut config = match (&tls.client_cert, &tls.client_key) {
+        (Some(cert), Some(key)) => {
+            let certs = rustls_pemfile::certs(&mut BufReader::new(File::open(cert)?))
+                .collect::<Result<Vec<_>, _>>()?;
+            let key = rustls_pemfile::private_key(&mut BufReader::new(File::open(key)?))?
+                .ok_or_else(|| format!("No private key found in {key}"))?;
+            builder.with_client_auth_cert(certs, key)?
+        }
+        (None, None) => builder.with_no_client_auth(),
+        _ => return Err("client-cert and client-key must be set together".into()),
+    };
+
+    config.alpn_protocols = tls
+        .alpn
+        .iter()
+        .map(|protocol| protocol.as_bytes().to_vec())
+        .collect();
+
+    if tls.insecure_skip_verify {
+        config
+            .dangerous()
+            .set_certificate_verifier(Arc::new(NoVerification(
+                crypto::ring::default_provider().signature_verification_algorithms,
+            )));
+    }
+
+    Ok(config)
+}
+
+/// Loads the certificate authorities of `ca-file`, or of the system if it is not set
+fn root_certificates(tls: &Tls) -> Result<RootCertStore, Box<dyn Error>> {
+    let mut roots = RootCertStore::empty();
+
+    match &tls.ca_file {
+        Some(ca_file) => {
+            for cert in rustls_pemfile::certs(&mut BufReader::new(File::open(ca_file)?)) {
+                roots.add(cert?)?;
+            }
+            if roots.is_empty() {
+                return Err(format!("No certificate found in {ca_file}").into());
+            }
+        }
+        None => {
+            // Some system certificates may be unsupported, they are ignored
+            roots.add_parsable_certificates(rustls_native_certs::load_native_certs()?);
+        }
+    }
+
+    Ok(roots)
+}
+
+/// Accepts any broker certificate, only the signatures of the handshake are checked
+#[derive(Debug)]
+struct NoVerification(WebPkiSupportedAlgorithms);
+
+impl ServerCertVerifier for NoVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, TlsError> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TlsError> {
+        verify_tls12_signature(message, cert, dss, &self.0)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TlsError> {
+        verify_tls13_signature(message, cert, dss, &self.0)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{BasicConstraints, CertificateParams, CertifiedIssuer, IsCa, KeyPair};
+    use rumqttc::tokio_rustls::rustls::ServerConfig;
+    use rumqttc::tokio_rustls::rustls::pki_types::PrivateKeyDer;
+    use rumqttc::tokio_rustls::rustls::server::WebPkiClientVerifier;
+    use rumqttc::tokio_rustls::{TlsAcceptor, TlsConnector};
+    use rumqttc::{
+        AsyncClient, ConnectReturnCode, Event, MqttOptions, Packet, TlsConfiguration, Transport,
+    };
+    use std::path::PathBuf;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// Self-signed certificates written in a temporary directory
+    struct Certificates {
+        root: PathBuf,
+        ca: CertifiedIssuer<'static, KeyPair>,
+    }
+
+    impl Certificates {
+        fn new(name: &str) -> Certificates {
+            let root = std::env::temp_dir().join(format!(
+                "mqtt-system-monitor-tls-{name}-{}",
+                std::process::id()
+            ));
+            std::fs::create_dir_all(&root).expect("Cannot create certificate directory");
+
+            let mut params = CertificateParams::default();
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            let ca = CertifiedIssuer::self_signed(params, KeyPair::generate().unwrap()).unwrap();
+            std::fs::write(root.join("ca.pem"), ca.pem()).expect("Cannot write CA");
+
+            Certificates { root, ca }
+        }
+
+        /// Writes a certificate for `localhost` signed by the CA, and returns its chain and key
+        fn issue(&self, name: &str) -> (Vec<CertificateDer<'static>>, PrivateKeyDer<'static>) {
+            let key = KeyPair::generate().unwrap();
+            let cert = CertificateParams::new(vec![String::from("localhost")])
+                .unwrap()
+                .signed_by(&key, &self.ca)
+                .unwrap();
+            std::fs::write(self.path(&format!("{name}.pem")), cert.pem())
+                .expect("Cannot write certificate");
+            std::fs::write(self.path(&format!("{name}.key")), key.serialize_pem())
+                .expect("Cannot write key");
+
+            (
+                vec![cert.der().clone()],
+                PrivateKeyDer::try_from(key.serialize_der()).unwrap(),
+            )
+        }
+
+        fn path(&self, name: &str) -> String {
+            self.root.join(name).to_string_lossy().to_string()
+        }
+
+        fn ca_der(&self) -> CertificateDer<'static> {
+            self.ca.der().clone()
+        }
+    }
+
+    impl Drop for Certificates {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.root);
+        }
+    }
+
+    /// Accepts a single TLS connection and returns the stream
+    async fn accept(
+        listener: &TcpListener,
+        config: ServerConfig,
+    ) -> std::io::Result<rumqttc::tokio_rustls::server::TlsStream<TcpStream>> {
+        let (stream, _) = listener.accept().await?;
+        TlsAcceptor::from(Arc::new(config)).accept(stream).await
+    }
+
+    /// Opens a TLS connection to the listener with the client configuration
+    async fn connect(listener: &TcpListener, config: ClientConfig) -> std::io::Result<()> {
+        let stream = TcpStream::connect(listener.local_addr()?).await?;
+        let mut stream = TlsConnector::from(Arc::new(config))
+            .connect(ServerName::try_from("localhost").unwrap(), stream)
+            .await?;
+        stream.shutdown().await
+    }
+
+    #[test]
+    fn test_client_config() {
+        let certificates = Certificates::new("config");
+        certificates.issue("client");
+
+        let tls = Tls {
+            ca_file: Some(certificates.path("ca.pem")),
+            client_cert: Some(certificates.path("client.pem")),
+            client_key: Some(certificates.path("client.key")),
+            alpn: vec![String::from("mqtt"), String::from("x-amzn-mqtt-ca")],
+            insecure_skip_verify: false,
+        };
+        let config = client_config(&tls).expect("Cannot configure TLS");
+        assert!(config.client_auth_cert_resolver.has_certs());
+        assert_eq!(
+            config.alpn_protocols,
+            vec![b"mqtt".to_vec(), b"x-amzn-mqtt-ca".to_vec()]
+        );
+
+        let missing_key = Tls {
+            client_key: None,
+            ..tls
+        };
+        assert!(client_config(&missing_key).is_err());
+
+        let missing_ca = Tls {
+            ca_file: Some(certificates.path("missing.pem")),
+            ..Default::default()
+        };
+        assert!(client_config(&missing_ca).is_err());
+
+        let no_certificate = Tls {
+            ca_file: Some(certificates.path("client.key")),
+            ..Default::default()
+        };
+        assert!(client_config(&no_certificate).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verification() {
+        let certificates = Certificates::new("verification");
+        let (chain, key) = certificates.issue("broker");
+        let server_config = || {
+            ServerConfig::builder()
+                .with_no_client_auth()
+                .with_single_cert(chain.clone(), key.clone_key())
+                .unwrap()
+        };
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+        // The broker certificate is signed by the CA
+        let trusted = Tls {
+            ca_file: Some(certificates.path("ca.pem")),
+            ..Default::default()
+        };
+        let (server, client) = tokio::join!(
+            accept(&listener, server_config()),
+            connect(&listener, client_config(&trusted).unwrap())
+        );
+        assert!(server.is_ok());
+        assert!(client.is_ok());
+
+        // The broker certificate is not signed by another CA
+        let other = Certificates::new("verification-other");
+        let untrusted = Tls {
+            ca_file: Some(other.path("ca.pem")),
+            ..Default::default()
+        };
+        let (_, client) = tokio::join!(
+            accept(&listener, server_config()),
+            connect(&listener, client_config(&untrusted).unwrap())
+        );
+        assert!(client.is_err());
+
+        // The verification is skipped
+        let insecure = Tls {
+            insecure_skip_verify: true,
+            ..untrusted
+        };
+        let (server, client) = tokio::join!(
+            accept(&listener, server_config()),
+            connect(&listener, client_config(&insecure).unwrap())
+        );
+        assert!(server.is_ok());
+        assert!(client.is_ok());
+    }
+
+    /// Connects to a fake broker requiring a client certificate through the TLS transport
+    #[tokio::test]
+    async fn test_mqtt_connection() {
+        let certificates = Certificates::new("mqtt");
+        let (chain, key) = certificates.issue("broker");
+        certificates.issue("client");
+
+        let mut roots = RootCertStore::empty();
+        roots.add(certificates.ca_der()).unwrap();
+        let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+            .build()
+            .unwrap();
+        let server_config = ServerConfig::builder()
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(chain, key)
+            .unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let broker = async {
+            let mut stream = accept(&listener, server_config).await?;
+            // The CONNECT packet is short enough for its length to fit in one byte
+            let mut connect = [0; 2];
+            stream.read_exact(&mut connect).await?;
+            let mut payload = vec![0; connect[1] as usize];
+            stream.read_exact(&mut payload).await?;
+            // CONNACK, no session present, connection accepted
+            stream.write_all(&[0x20, 0x02, 0x00, 0x00]).await?;
+            stream.flush().await?;
+            Ok::<u8, std::io::Error>(connect[0])
+        };
+
+        let tls = Tls {
+            ca_file: Some(certificates.path("ca.pem")),
+            client_cert: Some(certificates.path("client.pem")),
+            client_key: Some(certificates.path("client.key")),
+            ..Default::default()
+        };
+        let mut options = MqttOptions::new("test", "localhost", port);
+        options.set_credentials("user", "password");
+        options.set_transport(Transport::tls_with_config(TlsConfiguration::Rustls(
+            Arc::new(client_config(&tls).unwrap()),
+        )));
+        let (_client, mut eventloop) = AsyncClient::new(options, 10);
+
+        let (broker, event) = tokio::join!(broker, eventloop.poll());
+
+        // CONNECT packet type
+        assert_eq!(broker.unwrap(), 0x10);
+        match event {
+            Ok(Event::Incoming(Packet::ConnAck(connack))) => {
+                assert_eq!(connack.code, ConnectReturnCode::Success)
+            }
+            event => panic!("Unexpected event {event:?}"),
+        }
+    }
+}
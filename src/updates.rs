@@ -0,0 +1,244 @@
+use crate::configuration::PackageManager;
+use std::cmp::Ordering;
+use std::path::Path;
+
+/// File created by Debian based distributions when a reboot is needed to apply updates
+pub const REBOOT_REQUIRED_PATH: &str = "/var/run/reboot-required";
+
+/// Location of the modules of the installed kernels
+pub const MODULES_ROOT: &str = "/lib/modules";
+
+/// Location of the kernel images
+pub const BOOT_ROOT: &str = "/boot";
+
+/// Path of the release of the running kernel
+pub const OSRELEASE_PATH: &str = "/proc/sys/kernel/osrelease";
+
+/// Returns the shell command listing the pending updates of the package manager
+///
+/// The commands exit successfully when there is no update, so that only real failures are
+/// reported as errors
+pub fn command(package_manager: &PackageManager) -> &'static str {
+    match package_manager {
+        // Uses the package lists of the last `apt update`, which needs root to refresh them
+        PackageManager::Apt => "apt-get --simulate --quiet --quiet upgrade",
+        // Exits with 100 if updates are available and 1 on error
+        PackageManager::Dnf => "dnf --quiet check-update; [ $? -ne 1 ]",
+        // Exits with 2 if no update is available and 1 on error
+        PackageManager::Pacman => "checkupdates; [ $? -ne 1 ]",
+    }
+}
+
+/// Counts the pending updates in the output of the package manager command
+///
+/// ## Example
+///
+/// ```
+/// use mqtt_system_monitor::configuration::PackageManager;
+/// use mqtt_system_monitor::updates;
+///
+/// let output = "linux 6.11.2.arch1-1 -> 6.11.3.arch1-1\nmesa 1:24.2.3-1 -> 1:24.2.4-1\n";
+///
+/// assert_eq!(updates::count(&PackageManager::Pacman, output), 2);
+/// ```
+pub fn count(package_manager: &PackageManager, output: &str) -> u64 {
+    let lines = output.lines().filter(|line| !line.trim().is_empty());
+
+    match package_manager {
+        PackageManager::Apt => lines.filter(|line| line.starts_with("Inst ")).count() as u64,
+        PackageManager::Dnf => lines
+            .take_while(|line| !line.starts_with("Obsoleting Packages"))
+            // Long package names are followed by the version and the repository on the next line
+            .filter(|line| {
+                !line.starts_with(char::is_whitespace)
+                    && matches!(line.split_whitespace().count(), 1 | 3)
+            })
+            .count() as u64,
+        PackageManager::Pacman => lines.count() as u64,
+    }
+}
+
+/// Tells if a reboot is required to apply the updates
+///
+/// A reboot is required if the `flag` file exists, or if the running kernel is not the newest
+/// kernel installed in `modules` with the same flavor. A kernel is installed if its image exists,
+/// either in its modules directory or in `boot`, so that the leftovers of removed kernels are
+/// ignored.
+pub fn reboot_required(flag: &Path, modules: &Path, boot: &Path, running_kernel: &str) -> bool {
+    if flag.exists() {
+        return true;
+    }
+
+    let Ok(entries) = std::fs::read_dir(modules) else {
+        return false;
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|release| flavor(release) == flavor(running_kernel))
+        .filter(|release| {
+            modules.join(release).join("vmlinuz").exists()
+                || boot.join(format!("vmlinuz-{release}")).exists()
+        })
+        .max_by(|a, b| compare_versions(a, b))
+        .is_some_and(|newest| newest != running_kernel)
+}
+
+/// Returns the release of the running kernel
+pub fn running_kernel() -> Option<String> {
+    std::fs::read_to_string(OSRELEASE_PATH)
+        .ok()
+        .map(|release| release.trim().to_string())
+}
+
+/// Returns the flavor of a kernel release, made of its trailing non-numeric parts
+///
+/// For example, the flavor of `6.1.0-25-rt-amd64` is `rt-amd64`. The releases without a flavor,
+/// like `6.10.12-200.fc40.x86_64`, have an empty one.
+fn flavor(release: &str) -> &str {
+    let mut start = release.len();
+    for (index, _) in release.rmatch_indices('-') {
+        if release[index + 1..].starts_with(|c: char| c.is_ascii_digit()) {
+            break;
+        }
+        start = index + 1;
+    }
+
+    &release[start..]
+}
+
+/// Compares two versions, where the numeric parts are compared as numbers
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let mut a = chunks(a);
+    let mut b = chunks(b);
+
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                _ => a.cmp(b),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// Splits a version into its numeric and non-numeric parts
+fn chunks(version: &str) -> impl Iterator<Item = &str> {
+    let mut rest = version;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let end = rest
+            .find(|c: char| c.is_ascii_digit() != first.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (chunk, remaining) = rest.split_at(end);
+        rest = remaining;
+        Some(chunk)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count() {
+        let apt = "\
+NOTE: This is only a simulation!
+      apt-get needs root privileges for real execution.
+Inst libssl3 [3.0.13-0ubuntu3.3] (3.0.13-0ubuntu3.4 Ubuntu:24.04/noble-updates [amd64])
+Inst openssl [3.0.13-0ubuntu3.3] (3.0.13-0ubuntu3.4 Ubuntu:24.04/noble-updates [amd64])
+Conf libssl3 (3.0.13-0ubuntu3.4 Ubuntu:24.04/noble-updates [amd64])
+Conf openssl (3.0.13-0ubuntu3.4 Ubuntu:24.04/noble-updates [amd64])
+";
+        let dnf = "
+kernel.x86_64                       6.10.12-200.fc40                 updates
+firefox.x86_64                      131.0.2-1.fc40                   updates
+python3-very-long-package-name.noarch
+                                    1.2.3-1.fc40                     updates
+Obsoleting Packages
+grub2-tools.x86_64                  1:2.06-121.fc40                  updates
+    grub2-tools.x86_64              1:2.06-120.fc40                  @updates
+";
+        let pacman = "linux 6.11.2.arch1-1 -> 6.11.3.arch1-1\n";
+
+        assert_eq!(count(&PackageManager::Apt, apt), 2);
+        assert_eq!(count(&PackageManager::Dnf, dnf), 3);
+        assert_eq!(count(&PackageManager::Pacman, pacman), 1);
+        assert_eq!(count(&PackageManager::Pacman, ""), 0);
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(
+            compare_versions("6.8.0-47-generic", "6.8.0-45-generic"),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_versions("6.10.12-200.fc40.x86_64", "6.9.9-200.fc40.x86_64"),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_versions("6.1.0-25-amd64", "6.1.0-25-amd64"),
+            Ordering::Equal
+        );
+        assert_eq!(compare_versions("6.1", "6.1.0"), Ordering::Less);
+
+        // The flavors are not ordered by version, so only the same flavor is compared
+        assert_eq!(
+            compare_versions("6.1.0-25-rt-amd64", "6.1.0-25-amd64"),
+            Ordering::Greater
+        );
+        assert_eq!(flavor("6.1.0-25-rt-amd64"), "rt-amd64");
+        assert_eq!(flavor("6.1.0-25-amd64"), "amd64");
+        assert_eq!(flavor("6.8.0-47-generic"), "generic");
+        assert_eq!(flavor("6.10.12-200.fc40.x86_64"), "");
+        assert_eq!(flavor("6.11.3-arch1-1"), "");
+    }
+
+    #[test]
+    fn test_reboot_required() {
+        let root = std::env::temp_dir().join(format!(
+            "mqtt-system-monitor-updates-{}",
+            std::process::id()
+        ));
+        let modules = root.join("modules");
+        let boot = root.join("boot");
+        let flag = root.join("reboot-required");
+        std::fs::create_dir_all(&boot).expect("Cannot create boot");
+        for release in ["6.8.0-45-generic", "6.8.0-47-generic"] {
+            std::fs::create_dir_all(modules.join(release)).expect("Cannot create modules");
+            std::fs::write(modules.join(release).join("vmlinuz"), "").expect("Cannot write image");
+        }
+        // Leftover of a removed kernel
+        std::fs::create_dir_all(modules.join("6.8.0-49-generic")).expect("Cannot create modules");
+
+        let up_to_date = reboot_required(&flag, &modules, &boot, "6.8.0-47-generic");
+        let new_kernel = reboot_required(&flag, &modules, &boot, "6.8.0-45-generic");
+        let no_modules = reboot_required(&flag, &root.join("missing"), &boot, "6.8.0-45-generic");
+
+        // Debian places the images in /boot, and installs several flavors side by side
+        for release in ["6.1.0-25-amd64", "6.1.0-25-rt-amd64"] {
+            std::fs::create_dir_all(modules.join(release)).expect("Cannot create modules");
+            std::fs::write(boot.join(format!("vmlinuz-{release}")), "")
+                .expect("Cannot write image");
+        }
+        let other_flavor = reboot_required(&flag, &modules, &boot, "6.1.0-25-amd64");
+
+        std::fs::write(&flag, "*** System restart required ***\n").expect("Cannot write flag");
+        let flagged = reboot_required(&flag, &modules, &boot, "6.8.0-47-generic");
+        std::fs::remove_dir_all(&root).expect("Cannot remove fake root");
+
+        assert!(!up_to_date);
+        assert!(new_kernel);
+        assert!(!no_modules);
+        assert!(!other_flavor);
+        assert!(flagged);
+    }
+}
@@ -0,0 +1,72 @@
+use serde_json::Value;
+use std::error::Error;
+
+/// Converts the output of a command or the content of a file into a value
+///
+/// If `json_path` is set, the output is parsed as JSON and the value is searched with the path,
+/// made of object keys or array indices separated by dots. Otherwise, the output is a number if
+/// it can be parsed as one, or a string.
+///
+/// ## Example
+///
+/// ```
+/// use mqtt_system_monitor::value;
+/// use serde_json::Value;
+///
+/// assert_eq!(value::parse("12.5\n", None).unwrap(), Value::from(12.5));
+/// assert_eq!(
+///     value::parse(r#"{"ups": [{"charge": 100}]}"#, Some("ups.0.charge")).unwrap(),
+///     Value::from(100)
+/// );
+/// ```
+pub fn parse(output: &str, json_path: Option<&str>) -> Result<Value, Box<dyn Error>> {
+    let output = output.trim();
+
+    let Some(json_path) = json_path else {
+        return Ok(match output.parse::<f64>() {
+            Ok(number) => Value::from(number),
+            Err(_) => Value::from(output),
+        });
+    };
+
+    let mut value = serde_json::from_str::<Value>(output)?;
+    for key in json_path.split('.').filter(|key| !key.is_empty()) {
+        value = match value {
+            Value::Object(mut map) => map.remove(key),
+            Value::Array(mut array) => key
+                .parse::<usize>()
+                .ok()
+                .filter(|index| *index < array.len())
+                .map(|index| array.swap_remove(index)),
+            _ => None,
+        }
+        .ok_or_else(|| format!("`{key}` not found in `{output}`"))?;
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("3\n", None).unwrap(), Value::from(3.0));
+        assert_eq!(parse(" ok \n", None).unwrap(), Value::from("ok"));
+
+        let json = r#"{"battery": {"charge": 87, "status": "OL"}, "list": [1, 2]}"#;
+        assert_eq!(
+            parse(json, Some("battery.charge")).unwrap(),
+            Value::from(87)
+        );
+        assert_eq!(
+            parse(json, Some("battery.status")).unwrap(),
+            Value::from("OL")
+        );
+        assert_eq!(parse(json, Some("list.1")).unwrap(), Value::from(2));
+        assert!(parse(json, Some("list.2")).is_err());
+        assert!(parse(json, Some("missing")).is_err());
+        assert!(parse("not json", Some("key")).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+/// Path of the wireless statistics provided by the kernel
+pub const WIRELESS_PATH: &str = "/proc/net/wireless";
+
+/// Value reported by the drivers when the noise level is not available
+const NOISE_UNAVAILABLE: f32 = -256.0;
+
+/// Signal quality of a wireless interface
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct WirelessStats {
+    /// Link quality, on a scale that depends on the driver (usually out of 70)
+    pub link_quality: f32,
+
+    /// Signal level in dBm
+    pub signal_level: f32,
+
+    /// Noise level in dBm, if reported by the driver
+    pub noise_level: Option<f32>,
+}
+
+/// Reads the signal quality of all the wireless interfaces
+///
+/// Returns an empty map if the statistics are not available
+pub fn read() -> HashMap<String, WirelessStats> {
+    std::fs::read_to_string(WIRELESS_PATH)
+        .map(|content| parse(&content))
+        .unwrap_or_default()
+}
+
+/// Parses the content of `/proc/net/wireless`
+///
+/// The two header lines are skipped. The values can end with a `.` when they were updated since
+/// the last read.
+///
+/// ## Example
+///
+/// ```
+/// use mqtt_system_monitor::wireless;
+///
+/// let stats = wireless::parse(" wlan0: 0000   54.  -56.  -256        0      0      0      0      0        0");
+///
+/// assert_eq!(stats["wlan0"].link_quality, 54.0);
+/// assert_eq!(stats["wlan0"].signal_level, -56.0);
+/// assert_eq!(stats["wlan0"].noise_level, None);
+/// ```
+pub fn parse(content: &str) -> HashMap<String, WirelessStats> {
+    let mut map = HashMap::new();
+
+    for line in content.lines() {
+        let Some((interface, values)) = line.split_once(':') else {
+            continue;
+        };
+        let fields: Vec<&str> = values.split_whitespace().collect();
+        if fields.len() < 4 {
+            continue;
+        }
+
+        let value = |index: usize| fields[index].trim_end_matches('.').parse::<f32>().ok();
+        let (Some(link_quality), Some(signal_level)) = (value(1), value(2)) else {
+            continue;
+        };
+
+        map.insert(
+            interface.trim().to_string(),
+            WirelessStats {
+                link_quality,
+                signal_level,
+                noise_level: value(3).filter(|noise| *noise != NOISE_UNAVAILABLE),
+            },
+        );
+    }
+
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let content = "\
+Inter-| sta-|   Quality        |   Discarded packets               | Missed | WE
+ face | tus | link level noise |  nwid  crypt   frag  retry   misc | beacon | 22
+ wlan0: 0000   47.  -63.  -256        0      0      0      0     12        0
+wlp3s0: 0000   70   -35   -92         0      0      0      3      0        0
+";
+        let stats = parse(content);
+
+        assert_eq!(stats.len(), 2);
+        assert_eq!(
+            stats["wlan0"],
+            WirelessStats {
+                link_quality: 47.0,
+                signal_level: -63.0,
+                noise_level: None,
+            }
+        );
+        assert_eq!(
+            stats["wlp3s0"],
+            WirelessStats {
+                link_quality: 70.0,
+                signal_level: -35.0,
+                noise_level: Some(-92.0),
+            }
+        );
+    }
+}
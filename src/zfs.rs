@@ -0,0 +1,263 @@
+use crate::command;
+use log::warn;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Command used to query the state of the ZFS pools
+pub const ZPOOL: &str = "zpool";
+
+/// Possible values of the health of a pool
+pub const HEALTHS: &[&str] = &[
+    "ONLINE",
+    "DEGRADED",
+    "FAULTED",
+    "OFFLINE",
+    "UNAVAIL",
+    "REMOVED",
+    "SUSPENDED",
+];
+
+/// State of a ZFS pool
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ZfsPool {
+    /// Health of the pool, for example `ONLINE` or `DEGRADED`
+    pub health: String,
+
+    /// Used capacity of the pool in %
+    pub capacity: f32,
+
+    /// Progress in % of the running scrub or resilver, if any
+    pub scan_progress: Option<f32>,
+}
+
+/// Maximum duration of a `zpool` query, `zpool status` can hang on a suspended pool
+pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Reads the state of the pools with `zpool list` and `zpool status`
+///
+/// The health and the capacity of all the imported pools are listed, because `zpool` fails if
+/// one of the given pools is missing. Only the monitored pools that are imported are then
+/// queried with `zpool status` for their scan progress. Pools that are not imported are missing
+/// from the map. Returns an empty map if `zpool list` fails or doesn't complete before
+/// [TIMEOUT].
+pub async fn read(zpool: &str, pools: &[String]) -> HashMap<String, ZfsPool> {
+    let Some(list) = run(zpool, &["list", "-H", "-o", "name,health,capacity"]).await else {
+        return HashMap::new();
+    };
+
+    let mut map = parse_list(&list);
+    map.retain(|name, _| pools.contains(name));
+    if map.is_empty() {
+        return map;
+    }
+
+    let mut args = vec!["status"];
+    args.extend(map.keys().map(String::as_str));
+    let progress = run(zpool, &args)
+        .await
+        .map(|status| parse_status(&status))
+        .unwrap_or_default();
+    for (name, pool) in map.iter_mut() {
+        pool.scan_progress = progress.get(name).copied();
+    }
+
+    map
+}
+
+/// Runs `zpool` with the arguments and returns its output, or `None` if it failed
+async fn run(zpool: &str, args: &[&str]) -> Option<String> {
+    command::execute(zpool, args, TIMEOUT)
+        .await
+        .inspect_err(|e| warn!("Cannot query the ZFS pools: {e}"))
+        .ok()
+}
+
+/// Parses the output of `zpool list -H -o name,health,capacity`
+///
+/// ## Example
+///
+/// ```
+/// use mqtt_system_monitor::zfs;
+///
+/// let pools = zfs::parse_list("tank\tDEGRADED\t42%\n");
+///
+/// assert_eq!(pools["tank"].health, "DEGRADED");
+/// assert_eq!(pools["tank"].capacity, 42.0);
+/// ```
+pub fn parse_list(output: &str) -> HashMap<String, ZfsPool> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let name = fields.next()?;
+            let health = fields.next()?;
+            let capacity = fields.next()?.trim_end_matches('%').parse().ok()?;
+
+            Some((
+                name.to_string(),
+                ZfsPool {
+                    health: health.to_string(),
+                    capacity,
+                    scan_progress: None,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Parses the output of `zpool status` and returns the progress of the running scans per pool
+///
+/// ## Example
+///
+/// ```
+/// use mqtt_system_monitor::zfs;
+///
+/// let progress = zfs::parse_status(
+///     "  pool: tank\n  scan: resilver in progress\n\t0B repaired, 12.50% done, 01:00:00 to go\n",
+/// );
+///
+/// assert_eq!(progress["tank"], 12.5);
+/// ```
+pub fn parse_status(output: &str) -> HashMap<String, f32> {
+    let mut map = HashMap::new();
+    let mut pool = None;
+
+    for line in output.lines() {
+        if let Some(name) = line.trim().strip_prefix("pool: ") {
+            pool = Some(name.trim().to_string());
+            continue;
+        }
+
+        let Some(pool) = &pool else {
+            continue;
+        };
+        if let Some(progress) = line
+            .split(',')
+            .find_map(|part| part.trim().strip_suffix("% done"))
+            .and_then(|progress| progress.parse::<f32>().ok())
+        {
+            map.insert(pool.clone(), progress);
+        }
+    }
+
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    const STATUS: &str = "  pool: backup
+ state: ONLINE
+  scan: scrub repaired 0B in 02:10:42 with 0 errors on Sun Oct 11 02:34:43 2026
+config:
+
+\tNAME        STATE     READ WRITE CKSUM
+\tbackup      ONLINE       0     0     0
+\t  sdc       ONLINE       0     0     0
+
+errors: No known data errors
+
+  pool: tank
+ state: DEGRADED
+status: One or more devices is currently being resilvered.  The pool will
+\tcontinue to function, possibly in a degraded state.
+action: Wait for the resilver to complete.
+  scan: resilver in progress since Fri Oct 16 08:12:01 2026
+\t1.23T scanned at 1.50G/s, 600G issued at 700M/s, 2.00T total
+\t300G resilvered, 29.30% done, 00:35:00 to go
+config:
+
+\tNAME             STATE     READ WRITE CKSUM
+\ttank             DEGRADED     0     0     0
+\t  mirror-0       DEGRADED     0     0     0
+\t    sda          ONLINE       0     0     0
+\t    replacing-1  DEGRADED     0     0     0
+\t      sdb        OFFLINE      0     0     0
+\t      sdd        ONLINE       0     0     0  (resilvering)
+
+errors: No known data errors
+";
+
+    #[test]
+    fn test_parse_list() {
+        let pools = parse_list("backup\tONLINE\t7%\ntank\tDEGRADED\t63%\ninvalid line\n");
+
+        assert_eq!(pools.len(), 2);
+        assert_eq!(
+            pools["tank"],
+            ZfsPool {
+                health: "DEGRADED".to_string(),
+                capacity: 63.0,
+                scan_progress: None,
+            }
+        );
+        assert_eq!(pools["backup"].health, "ONLINE");
+    }
+
+    #[test]
+    fn test_parse_status() {
+        let progress = parse_status(STATUS);
+
+        assert_eq!(progress.len(), 1);
+        assert_eq!(progress["tank"], 29.3);
+    }
+
+    /// Test the query with a fake `zpool`
+    #[tokio::test]
+    async fn test_read() -> Result<(), Box<dyn std::error::Error>> {
+        let directory =
+            std::env::temp_dir().join(format!("mqtt-system-monitor-zfs-{}", std::process::id()));
+        std::fs::create_dir_all(&directory)?;
+        std::fs::write(directory.join("status"), STATUS)?;
+        let zpool = directory.join("zpool");
+        std::fs::write(
+            &zpool,
+            format!(
+                "#!/bin/sh
+case \"$1\" in
+    list) printf 'backup\\tONLINE\\t7%%\\ntank\\tDEGRADED\\t63%%\\nother\\tONLINE\\t1%%\\n' ;;
+    status) shift; printf '%s\\n' \"$@\" | sort > '{}'; cat '{}' ;;
+esac
+",
+                directory.join("args").display(),
+                directory.join("status").display()
+            ),
+        )?;
+        std::fs::set_permissions(&zpool, std::fs::Permissions::from_mode(0o755))?;
+
+        let pools = vec!["backup".to_string(), "tank".to_string()];
+        let states = read(zpool.to_str().unwrap(), &pools).await;
+
+        assert_eq!(states.len(), 2);
+        assert_eq!(states["backup"].scan_progress, None);
+        assert_eq!(
+            states["tank"],
+            ZfsPool {
+                health: "DEGRADED".to_string(),
+                capacity: 63.0,
+                scan_progress: Some(29.3),
+            }
+        );
+        // Only the monitored pools are queried for their status
+        assert_eq!(
+            std::fs::read_to_string(directory.join("args"))?,
+            "backup\ntank\n"
+        );
+
+        // A failing or hanging zpool reports no pool
+        let states = read("/nonexistent/zpool", &pools).await;
+        assert!(states.is_empty());
+
+        std::fs::write(
+            &zpool,
+            "#!/bin/sh\nprintf 'tank\\tONLINE\\t1%%\\n'\nexit 1\n",
+        )?;
+        assert!(read(zpool.to_str().unwrap(), &pools).await.is_empty());
+
+        std::fs::remove_dir_all(&directory)?;
+
+        Ok(())
+    }
+}
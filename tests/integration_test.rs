@@ -1,27 +1,32 @@
 use minijinja::{Environment, context};
 use mqtt_system_monitor::configuration;
+use mqtt_system_monitor::configuration::MemorySensor;
 use mqtt_system_monitor::daemon::Daemon;
-use mqtt_system_monitor::home_assistant::Sensor;
-use serde_json::Value;
+use mqtt_system_monitor::diskstats;
+use mqtt_system_monitor::home_assistant::{PAYLOAD_AVAILABLE, RegistrationDescriptor, Sensor, key};
+use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::error::Error;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 use sysinfo::{Components, Networks};
 
 #[test]
 fn test_empty_values() -> Result<(), Box<dyn Error>> {
     let conf = configuration::Configuration::load("conf/mqtt-system-monitor.conf")?;
 
-    let mut daemon = Daemon::new(conf);
+    let mut daemon = Daemon::new(conf)?;
 
     let status = daemon.update_data();
     assert!(status.network.is_empty());
     assert!(status.temperature.is_empty());
+    assert!(status.disk.is_empty());
 
     let status = daemon.update_data();
 
     assert!(status.network.is_empty());
     assert!(status.temperature.is_empty());
+    assert!(status.disk.is_empty());
 
     daemon.register_sensors();
 
@@ -48,7 +53,7 @@ fn test_selection() -> Result<(), Box<dyn Error>> {
     let interface = conf.sensors.network.clone();
     let temp_sensor = conf.sensors.temperature.clone();
 
-    let mut daemon = Daemon::new(conf);
+    let mut daemon = Daemon::new(conf)?;
 
     daemon.register_sensors();
 
@@ -65,6 +70,10 @@ fn test_selection() -> Result<(), Box<dyn Error>> {
     // The first call, the transfer rate is always at 0. It can be non-zero after some time
     assert_eq!(network_status.rx, 0.0);
     assert_eq!(network_status.tx, 0.0);
+    assert_eq!(network_status.rx_packets, None);
+    assert_eq!(network_status.tx_packets, None);
+    assert_eq!(network_status.rx_total, None);
+    assert_eq!(network_status.tx_total, None);
     if !temp_sensor.is_empty() {
         assert!(!status.temperature.is_empty());
     }
@@ -100,19 +109,25 @@ fn test_registration() -> Result<(), Box<dyn Error>> {
         .map(|id| id.to_string().clone())
         .collect();
 
+    conf.sensors.disk = vec!["/".to_string()];
+
     let first_interface = conf.sensors.network.first().unwrap().clone();
-    let first_temp = conf.sensors.temperature.first().map(|s| s.clone());
+    let first_temp = conf.sensors.temperature.first().cloned();
 
     let prefix = "test_prefix";
     conf.mqtt.entity = "Test Entity".to_string();
 
-    let mut daemon = Daemon::new(conf);
+    let mut daemon = Daemon::new(conf)?;
     daemon.register_sensors();
     let descriptor = daemon.registration_descriptor();
     assert_eq!(
         descriptor.discovery_topic(prefix),
         "test_prefix/device/test_entity/config"
     );
+    assert_eq!(
+        RegistrationDescriptor::birth_topic(prefix),
+        "test_prefix/status"
+    );
 
     let json: HashMap<String, Value> = serde_json::from_str(descriptor.to_string().as_str())?;
     assert_eq!(json["device"]["name"].as_str().unwrap(), "Test Entity");
@@ -124,6 +139,18 @@ fn test_registration() -> Result<(), Box<dyn Error>> {
         json["state_topic"].as_str().unwrap(),
         "mqtt-system-monitor/test_entity/state"
     );
+    assert_eq!(
+        json["availability"][0]["topic"].as_str().unwrap(),
+        "mqtt-system-monitor/test_entity/availability"
+    );
+    assert_eq!(json["availability"][0]["payload_not_available"], "OFF");
+    assert_eq!(json["components"]["available"]["availability"], json!([]));
+    assert_eq!(
+        json["components"]["available"]["state_topic"]
+            .as_str()
+            .unwrap(),
+        "mqtt-system-monitor/test_entity/availability"
+    );
     if let Some(temp) = first_temp {
         assert_eq!(
             json["components"][format!("{temp}_temp")]["platform"]
@@ -180,10 +207,272 @@ fn test_registration() -> Result<(), Box<dyn Error>> {
             .unwrap(),
         format!("test_entity_{first_interface}_net_tx")
     );
+    // The details of the interfaces are not reported by default
+    assert!(json["components"][format!("{first_interface}_net_mac")].is_null());
+    assert_eq!(
+        json["components"]["root_disk_usage"]["unique_id"]
+            .as_str()
+            .unwrap(),
+        "test_entity_root_disk_usage"
+    );
+    assert_eq!(
+        json["components"]["root_disk_free"]["device_class"]
+            .as_str()
+            .unwrap(),
+        "data_size"
+    );
 
     Ok(())
 }
 
+#[test]
+fn test_process() -> Result<(), Box<dyn Error>> {
+    let pidfile =
+        std::env::temp_dir().join(format!("mqtt-system-monitor-{}.pid", std::process::id()));
+    std::fs::write(&pidfile, format!("{}\n", std::process::id()))?;
+
+    let mut conf = configuration::Configuration::load("conf/mqtt-system-monitor.conf")?;
+    conf.sensors.process = toml::from_str::<configuration::Sensors>(&format!(
+        r#"
+        [[process]]
+        name = "Test process"
+        pidfile = "{}"
+
+        [[process]]
+        name = "missing"
+        process-name = "missing-process"
+        "#,
+        pidfile.display()
+    ))?
+    .process;
+
+    let mut daemon = Daemon::new(conf)?;
+    daemon.register_sensors();
+
+    let status = daemon.update_data();
+    std::fs::remove_file(&pidfile)?;
+
+    assert!(
+        daemon
+            .registration_descriptor()
+            .has_sensor(Sensor::ProcessRunning("Test process".to_string()))
+    );
+
+    let process = &status.process["Test_process"];
+    assert_eq!(process.running, "ON");
+    assert!(process.memory > 0);
+
+    let missing = &status.process["missing"];
+    assert_eq!(missing.running, "OFF");
+    assert_eq!(missing.memory, 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_memory() -> Result<(), Box<dyn Error>> {
+    let mut conf = configuration::Configuration::load("conf/mqtt-system-monitor.conf")?;
+    conf.sensors.memory = vec![MemorySensor::Cached];
+
+    let mut daemon = Daemon::new(conf)?;
+    daemon.register_sensors();
+
+    let status = daemon.update_data();
+
+    // Only the selected memory sensors are filled
+    assert!(status.memory_cached.is_some());
+    assert_eq!(status.memory_buffers, None);
+    assert_eq!(status.memory_available, None);
+    assert_eq!(status.swap_usage, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_command() -> Result<(), Box<dyn Error>> {
+    let mut conf = configuration::Configuration::load("conf/mqtt-system-monitor.conf")?;
+    conf.sensors.command = toml::from_str::<configuration::Sensors>(
+        r#"
+        [[command]]
+        name = "answer"
+        command = "echo 42"
+        unit = "%"
+
+        [[command]]
+        name = "json"
+        command = "echo '{\"ups\": {\"status\": \"OL\"}}'"
+        json-path = "ups.status"
+
+        [[command]]
+        name = "cached"
+        command = "date +%s%N"
+        interval = 3600
+
+        [[command]]
+        name = "failing"
+        command = "exit 1"
+        "#,
+    )?
+    .command;
+
+    let mut daemon = Daemon::new(conf)?;
+    daemon.register_sensors();
+    daemon.spawn_collectors();
+
+    // The commands run in the background, wait for their first values
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let status = loop {
+        let status = daemon.update_data();
+        if ["answer", "json", "cached"]
+            .iter()
+            .all(|name| !status.command[*name].is_null())
+            || Instant::now() > deadline
+        {
+            break status;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    };
+    let cached = status.command["cached"].clone();
+
+    let env = template_environment(daemon.registration_descriptor());
+    let context = context!(value_json => status);
+
+    assert_eq!(get_value::<f64>(&env, &context, "answer_command")?, 42.0);
+    assert_eq!(get_value::<String>(&env, &context, "json_command")?, "OL");
+    assert_eq!(
+        get_value::<String>(&env, &context, "failing_command")?,
+        "none"
+    );
+
+    // The command is not run again before its interval elapsed
+    let status = daemon.update_data();
+    assert_eq!(status.command["cached"], cached);
+
+    Ok(())
+}
+
+#[test]
+fn test_file() -> Result<(), Box<dyn Error>> {
+    let path = std::env::temp_dir().join(format!(
+        "mqtt-system-monitor-capacity-{}",
+        std::process::id()
+    ));
+    std::fs::write(&path, "87\n")?;
+
+    let mut conf = configuration::Configuration::load("conf/mqtt-system-monitor.conf")?;
+    conf.sensors.file = toml::from_str::<configuration::Sensors>(&format!(
+        r#"
+        [[file]]
+        name = "capacity"
+        path = "{}"
+        unit = "%"
+        device-class = "battery"
+
+        [[file]]
+        name = "missing"
+        path = "/nonexistent/*/file"
+        "#,
+        path.display()
+    ))?
+    .file;
+
+    let mut daemon = Daemon::new(conf)?;
+    daemon.register_sensors();
+
+    let status = daemon.update_data();
+    std::fs::remove_file(&path)?;
+
+    let env = template_environment(daemon.registration_descriptor());
+    let context = context!(value_json => status);
+
+    assert_eq!(get_value::<f64>(&env, &context, "capacity_file")?, 87.0);
+    assert_eq!(get_value::<String>(&env, &context, "missing_file")?, "none");
+
+    Ok(())
+}
+
+#[test]
+fn test_power_supply() -> Result<(), Box<dyn Error>> {
+    let root = std::env::temp_dir().join(format!(
+        "mqtt-system-monitor-power-supply-{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(root.join("BAT0"))?;
+    std::fs::write(root.join("BAT0/type"), "Battery\n")?;
+    std::fs::write(root.join("BAT0/capacity"), "64\n")?;
+    std::fs::write(root.join("BAT0/status"), "Discharging\n")?;
+
+    let mut conf = configuration::Configuration::load("conf/mqtt-system-monitor.conf")?;
+    conf.sensors.power_supply = true;
+    conf.sensors.power_supply_root = root.display().to_string();
+
+    let mut daemon = Daemon::new(conf)?;
+    daemon.register_sensors();
+
+    let status = daemon.update_data();
+    std::fs::remove_dir_all(&root)?;
+
+    let descriptor = daemon.registration_descriptor();
+    assert!(descriptor.has_sensor(Sensor::BatteryLevel));
+    assert!(descriptor.has_sensor(Sensor::BatteryCharging));
+    // There is no AC adapter in the fake sysfs
+    assert!(!descriptor.has_sensor(Sensor::AcConnected));
+
+    assert_eq!(status.battery_level, Some(64.0));
+    assert_eq!(status.battery_charging, Some("OFF"));
+    assert_eq!(status.ac_connected, None);
+
+    Ok(())
+}
+
+#[test]
+fn test_hwmon() -> Result<(), Box<dyn Error>> {
+    let root =
+        std::env::temp_dir().join(format!("mqtt-system-monitor-hwmon-{}", std::process::id()));
+    std::fs::create_dir_all(root.join("hwmon2"))?;
+    std::fs::write(root.join("hwmon2/name"), "it8728\n")?;
+    std::fs::write(root.join("hwmon2/fan1_input"), "0\n")?;
+    std::fs::write(root.join("hwmon2/in1_input"), "12096\n")?;
+
+    let mut conf = configuration::Configuration::load("conf/mqtt-system-monitor.conf")?;
+    conf.sensors.fan = vec!["hwmon2_fan1".to_string(), "hwmon2_fan2".to_string()];
+    conf.sensors.voltage = vec!["hwmon2_in1".to_string()];
+    conf.sensors.hwmon_root = root.display().to_string();
+
+    let mut daemon = Daemon::new(conf)?;
+    daemon.register_sensors();
+
+    let status = daemon.update_data();
+    std::fs::remove_dir_all(&root)?;
+
+    let descriptor = daemon.registration_descriptor();
+    assert!(descriptor.has_sensor(Sensor::Fan("hwmon2_fan1".to_string(), String::new())));
+    // The second fan doesn't exist
+    assert!(!descriptor.has_sensor(Sensor::Fan("hwmon2_fan2".to_string(), String::new())));
+    assert!(descriptor.has_sensor(Sensor::Voltage("hwmon2_in1".to_string(), String::new())));
+
+    let env = template_environment(descriptor);
+    let context = context!(value_json => status);
+
+    // A stopped fan is reported as 0 RPM
+    assert_eq!(get_value::<f32>(&env, &context, "hwmon2_fan1_speed")?, 0.0);
+    assert_eq!(
+        get_value::<f32>(&env, &context, "hwmon2_in1_voltage")?,
+        12.096
+    );
+
+    Ok(())
+}
+
+fn template_environment(descriptor: &RegistrationDescriptor) -> Environment<'_> {
+    let mut env = Environment::new();
+    for (name, device) in descriptor.components() {
+        env.add_template(name, device.value_template())
+            .expect("Invalid expression");
+    }
+    env
+}
+
 fn get_value<T>(
     env: &Environment,
     context: &minijinja::Value,
@@ -194,8 +483,8 @@ where
 {
     T::from_str(
         &env.get_template(name)
-            .expect(&format!("Cannot find value {name}"))
-            .render(&context)
+            .unwrap_or_else(|_| panic!("Cannot find value {name}"))
+            .render(context)
             .expect("Failed to render value"),
     )
 }
@@ -216,10 +505,30 @@ fn test_templates() -> Result<(), Box<dyn Error>> {
         .map(|id| id.to_string().clone())
         .collect();
 
+    conf.sensors.disk = vec!["/".to_string(), "/not/mounted".to_string()];
+    conf.sensors.per_cpu = true;
+    conf.sensors.network_totals = true;
+    conf.sensors.network_details = true;
+    conf.sensors.network_packets = true;
+    conf.sensors.pressure = true;
+    conf.sensors.reboot_required = true;
+    conf.sensors.raid = vec!["md127".to_string()];
+    conf.sensors.zfs_pools = vec!["missing-pool".to_string()];
+    conf.sensors.systemd_units = vec!["nginx.service".to_string()];
+    conf.sensors.memory = vec![
+        MemorySensor::Swap,
+        MemorySensor::Available,
+        MemorySensor::Cached,
+        MemorySensor::Buffers,
+    ];
+    conf.sensors.disk_io = diskstats::read().into_keys().take(1).collect();
+    conf.sensors.disk_io.push("missing_device".to_string());
+    let first_device = key(conf.sensors.disk_io.first().unwrap());
+
     let first_interface = conf.sensors.network.first().unwrap().clone();
-    let first_temperature = conf.sensors.temperature.first().map(|s| s.clone());
+    let first_temperature = conf.sensors.temperature.first().cloned();
 
-    let mut daemon = Daemon::new(conf);
+    let mut daemon = Daemon::new(conf)?;
 
     daemon.register_sensors();
 
@@ -229,7 +538,7 @@ fn test_templates() -> Result<(), Box<dyn Error>> {
 
     println!("Input data: {status:?}");
     let mut env = Environment::new();
-    let context = context!(value_json => status);
+    let context = context!(value_json => status, value => PAYLOAD_AVAILABLE);
     for (name, device) in registration.components() {
         env.add_template(name, device.value_template())
             .expect("Invalid expression");
@@ -240,6 +549,10 @@ fn test_templates() -> Result<(), Box<dyn Error>> {
         assert!(!value.is_empty());
     }
 
+    assert_eq!(
+        get_value::<String>(&env, &context, "available")?,
+        PAYLOAD_AVAILABLE
+    );
     assert_eq!(
         get_value::<f32>(&env, &context, "cpu_usage")?,
         status.cpu_usage.unwrap()
@@ -256,6 +569,64 @@ fn test_templates() -> Result<(), Box<dyn Error>> {
         get_value::<String>(&env, &context, "disconnected_interface_net_tx")?,
         "none"
     );
+    // The reboot check runs in the background, which is not started here
+    assert_eq!(status.reboot_required, None);
+    assert_eq!(
+        get_value::<String>(&env, &context, "reboot_required")?,
+        "none"
+    );
+    if status.raid.is_empty() {
+        assert_eq!(
+            get_value::<String>(&env, &context, "md127_raid_problem")?,
+            "ON"
+        );
+    }
+    assert_eq!(
+        get_value::<String>(&env, &context, "missing_pool_zfs_problem")?,
+        "ON"
+    );
+    assert_eq!(
+        get_value::<String>(&env, &context, "missing_pool_zfs_capacity")?,
+        "none"
+    );
+    if let Some(cpu) = status.pressure.get("cpu") {
+        assert_eq!(
+            get_value::<f32>(&env, &context, "pressure_cpu_some_avg10")?,
+            cpu.some_avg10
+        );
+    }
+    assert_eq!(
+        get_value::<String>(&env, &context, "disconnected_interface_net_rx_errors")?,
+        "none"
+    );
+    assert_eq!(
+        get_value::<f64>(&env, &context, &format!("{first_interface}_net_rx_drops"))?,
+        status.network[&first_interface].rx_drops.unwrap()
+    );
+    assert_eq!(
+        get_value::<String>(&env, &context, "disconnected_interface_net_rx_total")?,
+        "none"
+    );
+    assert_eq!(
+        get_value::<u64>(&env, &context, &format!("{first_interface}_net_rx_total"))?,
+        status.network[&first_interface].rx_total.unwrap()
+    );
+    assert_eq!(
+        get_value::<String>(&env, &context, "disconnected_interface_net_link")?,
+        "OFF"
+    );
+    assert_eq!(
+        get_value::<String>(&env, &context, "disconnected_interface_net_mac")?,
+        "none"
+    );
+    assert_eq!(
+        get_value::<String>(&env, &context, &format!("{first_interface}_net_link"))?,
+        status.network[&first_interface].link.unwrap()
+    );
+    assert_eq!(
+        get_value::<String>(&env, &context, &format!("{first_interface}_net_mac"))?,
+        status.network[&first_interface].mac.clone().unwrap()
+    );
     assert_eq!(
         get_value::<f64>(&env, &context, &format!("{first_interface}_net_rx"))?,
         status.network[&first_interface].rx
@@ -265,6 +636,74 @@ fn test_templates() -> Result<(), Box<dyn Error>> {
         status.network[&first_interface].tx
     );
 
+    assert_eq!(
+        get_value::<f32>(&env, &context, "swap_usage")?,
+        status.swap_usage.unwrap()
+    );
+    assert_eq!(
+        get_value::<u64>(&env, &context, "memory_available")?,
+        status.memory_available.unwrap()
+    );
+    assert_eq!(
+        get_value::<u64>(&env, &context, "memory_cached")?,
+        status.memory_cached.unwrap()
+    );
+    assert_eq!(
+        get_value::<f32>(&env, &context, "cpu0_usage")?,
+        status.cpus[0].usage
+    );
+    assert_eq!(
+        get_value::<u64>(&env, &context, "cpu0_frequency")?,
+        status.cpus[0].frequency
+    );
+    assert_eq!(
+        get_value::<f64>(&env, &context, "load_average_5")?,
+        status.load_average_5.unwrap()
+    );
+    assert_eq!(
+        get_value::<String>(&env, &context, "uptime")?,
+        status.boot_time.clone().unwrap()
+    );
+    assert_eq!(
+        get_value::<String>(&env, &context, "mnt_not_mounted_disk_usage")?,
+        "none"
+    );
+    if let Some(disk) = status.disk.get("root") {
+        assert_eq!(
+            get_value::<f32>(&env, &context, "root_disk_usage")?,
+            disk.usage
+        );
+        assert_eq!(
+            get_value::<u64>(&env, &context, "root_disk_used")?,
+            disk.used
+        );
+        assert_eq!(
+            get_value::<u64>(&env, &context, "root_disk_free")?,
+            disk.free
+        );
+        if let Some(inode_usage) = disk.inode_usage {
+            assert_eq!(
+                get_value::<f32>(&env, &context, "root_disk_inode_usage")?,
+                inode_usage
+            );
+        }
+    }
+
+    assert_eq!(
+        get_value::<String>(&env, &context, "missing_device_disk_read")?,
+        "none"
+    );
+    if let Some(disk_io) = status.disk_io.get(&first_device) {
+        assert_eq!(
+            get_value::<f64>(&env, &context, &format!("{first_device}_disk_read"))?,
+            disk_io.read
+        );
+        assert_eq!(
+            get_value::<f64>(&env, &context, &format!("{first_device}_disk_write_ops"))?,
+            disk_io.write_ops
+        );
+    }
+
     if let Some(temp) = first_temperature {
         let name = format!("{temp}_temp");
         println!("Searching for {name}");